@@ -0,0 +1,651 @@
+//! Census of the objects on the grid
+//!
+//! Live cells are first gathered into clusters of cells close enough to affect each other. A
+//! cluster can still be several objects that just happen to sit near each other, like the two
+//! blocks of a bi-block, so each pair of connected pieces is run on its own and together, and only
+//! pieces that turn out differently together are kept as one object.
+//!
+//! Each object is then run until it repeats, and its phases in every orientation are compared to
+//! find a canonical form, which is looked up among the library's still lifes, oscillators, and
+//! spaceships to name it.
+use crate::{
+    library::LIBRARY,
+    life::{Life, LifeCell, NEIGHBORS},
+    pattern::Pattern,
+    rule::Rule,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Longest period to look for when classifying an object.
+const MAX_PERIOD: usize = 120;
+
+/// Fewest generations to run pieces of a cluster to see if they affect each other.
+const MIN_CHECK_GENERATIONS: usize = 12;
+
+/// Live cells, sorted, at coordinates that don't wrap.
+type Cells = Vec<(isize, isize)>;
+
+/// What an object does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    /// Never changes.
+    StillLife,
+
+    /// Repeats in place.
+    Oscillator { period: usize },
+
+    /// Repeats somewhere else.
+    Spaceship { period: usize },
+
+    /// Didn't repeat, or died out.
+    Unsettled,
+}
+
+impl Kind {
+    /// Describe the kind, like `p2 oscillator`.
+    pub fn description(&self) -> String {
+        match self {
+            Kind::StillLife => "still life".to_string(),
+            Kind::Oscillator { period } => format!("p{period} oscillator"),
+            Kind::Spaceship { period } => format!("p{period} spaceship"),
+            Kind::Unsettled => "unsettled".to_string(),
+        }
+    }
+}
+
+/// A kind of object and how many there are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CensusRow {
+    /// Name of the object, or a description if it isn't a known one.
+    pub name: String,
+
+    /// What it does.
+    pub kind: Kind,
+
+    /// Live cells in its smallest phase.
+    pub cells: usize,
+
+    /// Number of them on the grid.
+    pub count: usize,
+}
+
+/// Counts of each kind of object on the grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Census {
+    /// Generation the census was taken at.
+    pub generation: u64,
+
+    /// Kinds of object, most common first.
+    pub rows: Vec<CensusRow>,
+}
+
+/// Names of the library's objects that settle under a rule, by canonical form.
+pub struct Names(HashMap<Cells, &'static str>);
+
+impl Names {
+    /// Work out the canonical form of each library object under a rule.
+    pub fn new(rule: &Rule) -> Names {
+        Names(
+            LIBRARY
+                .iter()
+                .filter_map(|entry| {
+                    let analysis = analyze(&pattern_cells(&entry.pattern()), rule);
+                    (analysis.kind != Kind::Unsettled).then_some((analysis.canonical, entry.name))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A spaceship's phases, found by running it on its own.
+pub struct Spaceship {
+    /// Each phase over one period, trimmed to its bounding box, with where its top-left corner is
+    /// compared to the first phase's.
+    pub phases: Vec<(Pattern, (isize, isize))>,
+
+    /// How far it moves each period.
+    pub shift: (isize, isize),
+}
+
+/// Run a pattern on its own to see if it's a spaceship.
+pub fn spaceship(pattern: &Pattern, rule: &Rule) -> Option<Spaceship> {
+    let mut cells = pattern_cells(pattern);
+    let analysis = analyze(&cells, rule);
+
+    let Kind::Spaceship { period } = analysis.kind else {
+        return None;
+    };
+
+    let (_, first_origin) = normalize(&cells);
+    let mut phases = Vec::with_capacity(period);
+
+    for _ in 0..period {
+        let (shape, origin) = normalize(&cells);
+        let offset = (origin.0 - first_origin.0, origin.1 - first_origin.1);
+
+        phases.push((cells_pattern(&shape), offset));
+        cells = step(&cells, rule);
+    }
+
+    Some(Spaceship {
+        phases,
+        shift: analysis.shift,
+    })
+}
+
+/// How an object behaves when run on its own.
+struct Analysis {
+    /// What it does.
+    kind: Kind,
+
+    /// How far it moved by the time it repeated.
+    shift: (isize, isize),
+
+    /// Smallest of its phases in all eight orientations, shifted to the origin.
+    canonical: Cells,
+
+    /// Live cells in its phase with the fewest.
+    population: usize,
+}
+
+impl Census {
+    /// Take a census of the objects on the grid, with names already worked out for its rule.
+    pub fn take_named(life: &Life, names: &Names) -> Census {
+        let rule = life.rule();
+
+        // Check pieces of clusters for at least as long as the grid takes to repeat
+        let check_generations = life
+            .period()
+            .map_or(MIN_CHECK_GENERATIONS, |period| period.period as usize)
+            .clamp(MIN_CHECK_GENERATIONS, MAX_PERIOD);
+
+        let mut counts: HashMap<(String, Kind, usize), usize> = HashMap::new();
+
+        for cluster in clusters(life) {
+            for object in separate(cluster, &rule, check_generations) {
+                let analysis = analyze(&object, &rule);
+                let cells = analysis.population;
+
+                let name = match names.0.get(&analysis.canonical) {
+                    Some(name) => name.to_string(),
+                    None => format!("{}-cell {}", cells, analysis.kind.description()),
+                };
+
+                *counts.entry((name, analysis.kind, cells)).or_default() += 1;
+            }
+        }
+
+        let mut rows: Vec<CensusRow> = counts
+            .into_iter()
+            .map(|((name, kind, cells), count)| CensusRow {
+                name,
+                kind,
+                cells,
+                count,
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then(a.kind.cmp(&b.kind))
+                .then(a.cells.cmp(&b.cells))
+                .then(a.name.cmp(&b.name))
+        });
+
+        Census {
+            generation: life.generation(),
+            rows,
+        }
+    }
+
+    /// Get the total number of objects.
+    pub fn total(&self) -> usize {
+        self.rows.iter().map(|row| row.count).sum()
+    }
+
+    /// Write the census as a plain text table.
+    pub fn to_text(&self) -> String {
+        let name_width = self
+            .rows
+            .iter()
+            .map(|row| row.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Object".len());
+
+        let mut text = format!(
+            "Census at generation {}, {} objects\n\n{:>5}  {:name_width$}  {:>5}  Kind\n",
+            self.generation,
+            self.total(),
+            "Count",
+            "Object",
+            "Cells",
+        );
+
+        for row in &self.rows {
+            text.push_str(&format!(
+                "{:>5}  {:name_width$}  {:>5}  {}\n",
+                row.count,
+                row.name,
+                row.cells,
+                row.kind.description()
+            ));
+        }
+
+        text
+    }
+}
+
+/// Gather the live cells into clusters of cells within two of each other, which is as far apart
+/// as cells can be and still both touch the same dead cell.
+///
+/// Clusters that wrap around the edges are unwrapped, so their cells can be run without the grid.
+fn clusters(life: &Life) -> Vec<Cells> {
+    let (width, height) = (life.width(), life.height());
+    let cells = life.get_cells();
+    let mut seen = vec![vec![false; width]; height];
+    let mut clusters = Vec::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            if seen[y][x] || cells[y][x] != LifeCell::Alive {
+                continue;
+            }
+
+            let mut cluster = Vec::new();
+            let mut queue = VecDeque::from([(x, y, x as isize, y as isize)]);
+            seen[y][x] = true;
+
+            while let Some((gx, gy, ux, uy)) = queue.pop_front() {
+                cluster.push((ux, uy));
+
+                for dy in -2..=2 {
+                    for dx in -2..=2 {
+                        let Some((nx, ny)) =
+                            life.topology().neighbor(gx, gy, dx, dy, width, height)
+                        else {
+                            continue;
+                        };
+
+                        if !seen[ny][nx] && cells[ny][nx] == LifeCell::Alive {
+                            seen[ny][nx] = true;
+                            queue.push_back((nx, ny, ux + dx, uy + dy));
+                        }
+                    }
+                }
+            }
+
+            cluster.sort();
+            clusters.push(cluster);
+        }
+    }
+
+    clusters
+}
+
+/// Split a cluster into the objects it's made of.
+///
+/// The cluster is split into pieces of touching cells, and pieces near each other are joined back
+/// up if running them together turns out differently than running them apart.
+fn separate(cluster: Cells, rule: &Rule, generations: usize) -> Vec<Cells> {
+    let pieces = pieces(&cluster);
+
+    if pieces.len() == 1 {
+        return pieces;
+    }
+
+    // Union-find over the pieces
+    let mut parent: Vec<usize> = (0..pieces.len()).collect();
+
+    fn root(parent: &mut [usize], i: usize) -> usize {
+        let mut i = i;
+
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+
+        i
+    }
+
+    for a in 0..pieces.len() {
+        for b in a + 1..pieces.len() {
+            if near(&pieces[a], &pieces[b])
+                && !independent(&pieces[a], &pieces[b], rule, generations)
+            {
+                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                parent[ra] = rb;
+            }
+        }
+    }
+
+    let mut objects: HashMap<usize, Cells> = HashMap::new();
+
+    for (i, piece) in pieces.into_iter().enumerate() {
+        objects
+            .entry(root(&mut parent, i))
+            .or_default()
+            .extend(piece);
+    }
+
+    objects
+        .into_values()
+        .map(|mut object| {
+            object.sort();
+            object
+        })
+        .collect()
+}
+
+/// Split cells into pieces of cells that touch, including diagonally.
+fn pieces(cells: &Cells) -> Vec<Cells> {
+    let mut left: HashSet<(isize, isize)> = cells.iter().copied().collect();
+    let mut pieces = Vec::new();
+
+    for &start in cells {
+        if !left.remove(&start) {
+            continue;
+        }
+
+        let mut piece = vec![start];
+        let mut queue = VecDeque::from([start]);
+
+        while let Some((x, y)) = queue.pop_front() {
+            for (dx, dy) in NEIGHBORS {
+                if left.remove(&(x + dx, y + dy)) {
+                    piece.push((x + dx, y + dy));
+                    queue.push_back((x + dx, y + dy));
+                }
+            }
+        }
+
+        piece.sort();
+        pieces.push(piece);
+    }
+
+    pieces
+}
+
+/// Check if any cells of two pieces are within two of each other.
+fn near(a: &Cells, b: &Cells) -> bool {
+    a.iter().any(|(ax, ay)| {
+        b.iter()
+            .any(|(bx, by)| (ax - bx).abs() <= 2 && (ay - by).abs() <= 2)
+    })
+}
+
+/// Check if two pieces turn out the same run together as run apart.
+fn independent(a: &Cells, b: &Cells, rule: &Rule, generations: usize) -> bool {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    let mut both = union(&a, &b);
+
+    for _ in 0..generations {
+        a = step(&a, rule);
+        b = step(&b, rule);
+        both = step(&both, rule);
+
+        if union(&a, &b) != both {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Get the live cells of a pattern.
+fn pattern_cells(pattern: &Pattern) -> Cells {
+    (0..pattern.height())
+        .flat_map(|y| (0..pattern.width()).map(move |x| (x, y)))
+        .filter(|(x, y)| pattern.get_cell(*x, *y) == LifeCell::Alive)
+        .map(|(x, y)| (x as isize, y as isize))
+        .collect()
+}
+
+/// Make a pattern the size of the bounding box of cells that start at the origin.
+fn cells_pattern(cells: &Cells) -> Pattern {
+    let width = cells.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+    let mut pattern = Pattern::new(width as usize, height as usize);
+
+    for &(x, y) in cells {
+        pattern.set_cell(x as usize, y as usize, LifeCell::Alive);
+    }
+
+    pattern
+}
+
+/// Combine two sets of cells.
+fn union(a: &Cells, b: &Cells) -> Cells {
+    let mut cells: Cells = a.iter().chain(b).copied().collect();
+    cells.sort();
+    cells.dedup();
+    cells
+}
+
+/// Run cells for one generation on an endless grid.
+fn step(cells: &Cells, rule: &Rule) -> Cells {
+    let alive: HashSet<(isize, isize)> = cells.iter().copied().collect();
+    let mut counts: HashMap<(isize, isize), usize> = HashMap::new();
+
+    for &(x, y) in cells {
+        counts.entry((x, y)).or_default();
+
+        for (dx, dy) in NEIGHBORS {
+            *counts.entry((x + dx, y + dy)).or_default() += 1;
+        }
+    }
+
+    let mut next: Cells = counts
+        .into_iter()
+        .filter(|(cell, count)| rule.next_state(alive.contains(cell), *count))
+        .map(|(cell, _)| cell)
+        .collect();
+
+    next.sort();
+    next
+}
+
+/// Shift cells so the top-left of their bounding box is at the origin.
+///
+/// Returns the shifted cells and where the top-left was.
+fn normalize(cells: &Cells) -> (Cells, (isize, isize)) {
+    let min_x = cells.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
+
+    let mut shifted: Cells = cells.iter().map(|(x, y)| (x - min_x, y - min_y)).collect();
+    shifted.sort();
+
+    (shifted, (min_x, min_y))
+}
+
+/// Run an object on its own until it repeats, and work out what it is.
+fn analyze(cells: &Cells, rule: &Rule) -> Analysis {
+    let (first, first_origin) = normalize(cells);
+    let mut phases = vec![first.clone()];
+    let mut current = cells.clone();
+    let mut kind = Kind::Unsettled;
+    let mut shift = (0, 0);
+
+    for generation in 1..=MAX_PERIOD {
+        current = step(&current, rule);
+
+        if current.is_empty() {
+            break;
+        }
+
+        let (shape, origin) = normalize(&current);
+
+        if shape == first {
+            kind = match (generation, origin == first_origin) {
+                (1, true) => Kind::StillLife,
+                (period, true) => Kind::Oscillator { period },
+                (period, false) => Kind::Spaceship { period },
+            };
+            shift = (origin.0 - first_origin.0, origin.1 - first_origin.1);
+            break;
+        }
+
+        phases.push(shape);
+    }
+
+    // Unsettled objects are only compared as they are now
+    if kind == Kind::Unsettled {
+        phases.truncate(1);
+    }
+
+    let canonical = phases
+        .iter()
+        .flat_map(|phase| {
+            (0..8).map(move |orientation| {
+                let turned: Cells = phase
+                    .iter()
+                    .map(|&(x, y)| {
+                        let (x, y) = if orientation & 4 != 0 { (y, x) } else { (x, y) };
+                        let x = if orientation & 1 != 0 { -x } else { x };
+                        let y = if orientation & 2 != 0 { -y } else { y };
+                        (x, y)
+                    })
+                    .collect();
+
+                normalize(&turned).0
+            })
+        })
+        .min()
+        .unwrap_or_default();
+
+    let population = phases.iter().map(Vec::len).min().unwrap_or(0);
+
+    Analysis {
+        kind,
+        shift,
+        canonical,
+        population,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::PasteMode;
+
+    /// Get the live cells of a plaintext pattern, sorted.
+    fn cells(text: &str) -> Cells {
+        let mut cells = pattern_cells(&Pattern::from_plaintext(text).unwrap());
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn block_is_a_still_life() {
+        let analysis = analyze(&cells("OO\nOO"), &Rule::default());
+
+        assert_eq!(analysis.kind, Kind::StillLife);
+        assert_eq!(analysis.shift, (0, 0));
+        assert_eq!(analysis.population, 4);
+    }
+
+    #[test]
+    fn blinker_oscillates_with_period_2() {
+        let analysis = analyze(&cells("OOO"), &Rule::default());
+
+        assert_eq!(analysis.kind, Kind::Oscillator { period: 2 });
+        assert_eq!(analysis.population, 3);
+    }
+
+    #[test]
+    fn glider_moves_one_cell_diagonally_every_4_generations() {
+        let analysis = analyze(&cells(".O.\n..O\nOOO"), &Rule::default());
+
+        assert_eq!(analysis.kind, Kind::Spaceship { period: 4 });
+        assert_eq!(analysis.shift, (1, 1));
+        assert_eq!(analysis.population, 5);
+    }
+
+    #[test]
+    fn canonical_form_is_the_same_in_every_orientation_and_phase() {
+        let rule = Rule::default();
+        let glider = analyze(&cells(".O.\n..O\nOOO"), &rule).canonical;
+
+        // Turned and flipped
+        assert_eq!(analyze(&cells("OO.\nO.O\nO.."), &rule).canonical, glider);
+
+        // A later phase
+        assert_eq!(analyze(&cells("O.O\n.OO\n.O."), &rule).canonical, glider);
+
+        // Anywhere on the grid
+        let moved: Cells = cells(".O.\n..O\nOOO")
+            .into_iter()
+            .map(|(x, y)| (x - 7, y + 3))
+            .collect();
+        assert_eq!(analyze(&moved, &rule).canonical, glider);
+    }
+
+    #[test]
+    fn bi_block_separates_into_2_blocks() {
+        let rule = Rule::default();
+        let bi_block = cells("OO.OO\nOO.OO");
+
+        assert!(independent(
+            &cells("OO\nOO"),
+            &cells("...OO\n...OO"),
+            &rule,
+            MIN_CHECK_GENERATIONS
+        ));
+
+        let mut objects = separate(bi_block, &rule, MIN_CHECK_GENERATIONS);
+        objects.sort();
+
+        assert_eq!(objects, vec![cells("OO\nOO"), cells("...OO\n...OO")]);
+    }
+
+    #[test]
+    fn pieces_that_affect_each_other_stay_together() {
+        let rule = Rule::default();
+
+        // Apart the cells all die, but together they bring new cells to life
+        assert!(!independent(
+            &cells("OO"),
+            &cells("..\n..\n.O"),
+            &rule,
+            MIN_CHECK_GENERATIONS
+        ));
+
+        assert_eq!(
+            separate(cells("OO\n..\n.O"), &rule, MIN_CHECK_GENERATIONS),
+            vec![cells("OO\n..\n.O")]
+        );
+    }
+
+    #[test]
+    fn census_names_and_counts_objects() {
+        let mut life = Life::new();
+        life.init(30, 20);
+
+        for (text, x, y) in [
+            ("OO.OO\nOO.OO", 2, 2),
+            ("OO\nOO", 20, 2),
+            ("OOO", 2, 12),
+            (".O.\n..O\nOOO", 20, 12),
+        ] {
+            life.paste(&Pattern::from_plaintext(text).unwrap(), x, y, PasteMode::Or);
+        }
+
+        let census = Census::take_named(&life, &Names::new(&life.rule()));
+        let rows: Vec<(&str, Kind, usize)> = census
+            .rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.kind, row.count))
+            .collect();
+
+        assert_eq!(
+            rows,
+            vec![
+                ("Block", Kind::StillLife, 3),
+                ("Blinker", Kind::Oscillator { period: 2 }, 1),
+                ("Glider", Kind::Spaceship { period: 4 }, 1),
+            ]
+        );
+        assert_eq!(census.total(), 5);
+    }
+}
@@ -0,0 +1,465 @@
+//! Ex-style commands
+//!
+//! Typing `:` in the Life window opens a command line at the bottom, like vi. This module parses
+//! what's typed into a [`Command`], completes command names and arguments with Tab, and keeps the
+//! line being edited.
+use crate::rule::{KNOWN_RULES, Rule, Topology};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{fs, path::Path};
+
+/// Names of all the commands, for completion.
+const COMMANDS: [&str; 8] = [
+    "gen", "load", "quit", "rule", "save", "seed", "speed", "topology",
+];
+
+/// Most generations per second `:speed` allows.
+pub const MAX_SPEED: u32 = 1000;
+
+/// Most generations `:gen` steps at once.
+pub const MAX_GENERATIONS: u32 = 1_000_000;
+
+/// A command typed on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Show the rule, or change it.
+    Rule(Option<Rule>),
+
+    /// Replace the grid with a pattern from a file.
+    Load(String),
+
+    /// Save the pattern, to the given file or a new one.
+    Save(Option<String>),
+
+    /// Step forward a number of generations.
+    Gen(u32),
+
+    /// Show the running speed in generations per second, or change it.
+    Speed(Option<u32>),
+
+    /// Show how the edges are joined, or change it.
+    Topology(Option<Topology>),
+
+    /// Show the soup seed, or fill with the soup from a seed.
+    Seed(Option<u64>),
+
+    /// Quit the app.
+    Quit,
+}
+
+impl Command {
+    /// Parse a command line, without the `:`.
+    ///
+    /// Command names can be shortened to any prefix that isn't ambiguous.
+    pub fn parse(line: &str) -> crate::Result<Command> {
+        let line = line.trim();
+        let (name, arg) = match line.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (line, None),
+        };
+
+        let matches: Vec<&str> = COMMANDS
+            .into_iter()
+            .filter(|command| command.starts_with(name))
+            .collect();
+
+        // An exact match wins, so `:s` is ambiguous but `:save` and `:seed` aren't
+        let command = match matches.as_slice() {
+            _ if name.is_empty() => return Err("No command".into()),
+            _ if matches.contains(&name) => name,
+            [command] => command,
+            [] => return Err(format!("Not a command: {name}").into()),
+            _ => return Err(format!("Ambiguous command: {name}").into()),
+        };
+
+        let number = |what: &str| -> crate::Result<Option<u64>> {
+            arg.map(|arg| {
+                arg.parse()
+                    .map_err(|_| format!("{what} must be a number: {arg}").into())
+            })
+            .transpose()
+        };
+
+        Ok(match command {
+            "rule" => Command::Rule(arg.map(Rule::parse).transpose()?),
+
+            "load" => Command::Load(arg.ok_or("Usage: load <file>")?.to_string()),
+
+            "save" => Command::Save(arg.map(str::to_string)),
+
+            "gen" => Command::Gen(match number("Generations")? {
+                Some(generations) if (1..=MAX_GENERATIONS as u64).contains(&generations) => {
+                    generations as u32
+                }
+                Some(_) => {
+                    return Err(format!("Generations must be 1 to {MAX_GENERATIONS}").into());
+                }
+                None => 1,
+            }),
+
+            "speed" => Command::Speed(match number("Speed")? {
+                Some(speed) if (1..=MAX_SPEED as u64).contains(&speed) => Some(speed as u32),
+                Some(_) => return Err(format!("Speed must be 1 to {MAX_SPEED}").into()),
+                None => None,
+            }),
+
+            "topology" => Command::Topology(
+                arg.map(|arg| {
+                    Topology::from_name(arg).ok_or_else(|| format!("Unknown topology: {arg}"))
+                })
+                .transpose()?,
+            ),
+
+            "seed" => Command::Seed(number("Seed")?),
+
+            "quit" => Command::Quit,
+
+            _ => unreachable!("every command name is handled"),
+        })
+    }
+}
+
+/// Get the ways a partly typed command line could be completed, as whole lines.
+pub fn complete(line: &str) -> Vec<String> {
+    let Some((name, arg)) = line.split_once(' ') else {
+        return COMMANDS
+            .into_iter()
+            .filter(|command| command.starts_with(line))
+            .map(|command| format!("{command} "))
+            .collect();
+    };
+
+    let arg = arg.trim_start();
+
+    let candidates: Vec<String> = match name {
+        "rule" => KNOWN_RULES.iter().map(|rule| rule.to_string()).collect(),
+        "topology" => Topology::ALL
+            .iter()
+            .map(|topology| topology.name().to_string())
+            .collect(),
+        "load" | "save" => complete_path(arg),
+        _ => Vec::new(),
+    };
+
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&arg.to_lowercase()))
+        .map(|candidate| format!("{name} {candidate}"))
+        .collect()
+}
+
+/// Get the files and directories that a partly typed path could be, with `/` after directories.
+fn complete_path(partial: &str) -> Vec<String> {
+    let (dir, prefix) = match partial.rfind('/') {
+        Some(i) => (&partial[..=i], &partial[i + 1..]),
+        None => ("", partial),
+    };
+
+    let Ok(entries) = fs::read_dir(if dir.is_empty() {
+        Path::new(".")
+    } else {
+        Path::new(dir)
+    }) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+
+            // Hidden files only show up when asked for
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+
+            let slash = if entry.path().is_dir() { "/" } else { "" };
+            Some(format!("{dir}{name}{slash}"))
+        })
+        .collect();
+
+    paths.sort();
+    paths
+}
+
+/// What happened after a key on the command line.
+pub enum CommandLineResult {
+    /// Still typing.
+    Editing,
+
+    /// The command line was closed without running anything.
+    Cancel,
+
+    /// A command was entered, without the `:`.
+    Submit(String),
+}
+
+/// The command line being edited.
+pub struct CommandLine {
+    /// Text typed so far, without the `:`.
+    text: String,
+
+    /// Lines Tab cycles through, or empty if not completing.
+    completions: Vec<String>,
+
+    /// Index into the completions of the one shown.
+    completion: usize,
+
+    /// Index into the history of the line shown, or `None` if showing a new line.
+    history_index: Option<usize>,
+
+    /// Text typed before browsing the history, to come back to.
+    draft: String,
+}
+
+impl CommandLine {
+    /// Create a new empty command line.
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            completions: Vec::new(),
+            completion: 0,
+            history_index: None,
+            draft: String::new(),
+        }
+    }
+
+    /// Get the text typed so far, without the `:`.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Add pasted text to the end of the line. Only the first line is used, so a paste can't
+    /// submit the command.
+    pub fn paste(&mut self, text: &str) {
+        let line = text.lines().next().unwrap_or_default();
+
+        self.completions.clear();
+        self.text.extend(line.chars().filter(|c| !c.is_control()));
+    }
+
+    /// Handle a key, with earlier commands in `history`, oldest first.
+    pub fn handle_key(&mut self, key_event: &KeyEvent, history: &[String]) -> CommandLineResult {
+        if !matches!(key_event.code, KeyCode::Tab | KeyCode::BackTab) {
+            self.completions.clear();
+        }
+
+        match key_event.code {
+            KeyCode::Esc => return CommandLineResult::Cancel,
+
+            KeyCode::Enter => return CommandLineResult::Submit(self.text.clone()),
+
+            // Backspacing past the `:` closes the command line, like vi
+            KeyCode::Backspace if self.text.is_empty() => return CommandLineResult::Cancel,
+
+            KeyCode::Backspace => {
+                self.text.pop();
+            }
+
+            KeyCode::Char('u') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.text.clear();
+            }
+
+            KeyCode::Tab | KeyCode::BackTab => self.complete(key_event.code == KeyCode::Tab),
+
+            KeyCode::Up => {
+                let index = match self.history_index {
+                    Some(index) => index.saturating_sub(1),
+                    None if history.is_empty() => return CommandLineResult::Editing,
+                    None => {
+                        self.draft = self.text.clone();
+                        history.len() - 1
+                    }
+                };
+
+                self.history_index = Some(index);
+                self.text = history[index].clone();
+            }
+
+            KeyCode::Down => {
+                if let Some(index) = self.history_index {
+                    if index + 1 < history.len() {
+                        self.history_index = Some(index + 1);
+                        self.text = history[index + 1].clone();
+                    } else {
+                        self.history_index = None;
+                        self.text = self.draft.clone();
+                    }
+                }
+            }
+
+            KeyCode::Char(c) => self.text.push(c),
+
+            _ => (),
+        }
+
+        CommandLineResult::Editing
+    }
+
+    /// Complete the line, or show the next or previous completion if already completing.
+    fn complete(&mut self, forward: bool) {
+        if self.completions.is_empty() {
+            self.completions = complete(&self.text);
+            self.completion = 0;
+
+            // Remember the typed text so cycling all the way around comes back to it
+            if self.completions.len() > 1 {
+                self.completions.push(self.text.clone());
+            }
+
+            if !forward && !self.completions.is_empty() {
+                self.completion = self.completions.len().saturating_sub(2);
+            }
+        } else if forward {
+            self.completion = (self.completion + 1) % self.completions.len();
+        } else {
+            self.completion =
+                (self.completion + self.completions.len() - 1) % self.completions.len();
+        }
+
+        if let Some(line) = self.completions.get(self.completion) {
+            self.text = line.clone();
+        }
+
+        // A single completion is final, so the next Tab completes from there
+        if self.completions.len() == 1 {
+            self.completions.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Type some keys into a new command line and get what happened after the last one.
+    fn type_keys(codes: &[KeyCode], history: &[String]) -> (CommandLine, CommandLineResult) {
+        let mut command_line = CommandLine::new();
+        let mut result = CommandLineResult::Editing;
+
+        for code in codes {
+            result = command_line.handle_key(&KeyEvent::from(*code), history);
+        }
+
+        (command_line, result)
+    }
+
+    /// Get the key codes to type some text.
+    fn chars(text: &str) -> Vec<KeyCode> {
+        text.chars().map(KeyCode::Char).collect()
+    }
+
+    #[test]
+    fn parses_commands_and_arguments() {
+        assert_eq!(
+            Command::parse("rule B36/S23").unwrap(),
+            Command::Rule(Some(Rule::parse("B36/S23").unwrap()))
+        );
+        assert_eq!(Command::parse("rule").unwrap(), Command::Rule(None));
+        assert_eq!(
+            Command::parse("  load  glider.rle ").unwrap(),
+            Command::Load("glider.rle".to_string())
+        );
+        assert_eq!(Command::parse("save").unwrap(), Command::Save(None));
+        assert_eq!(Command::parse("gen").unwrap(), Command::Gen(1));
+        assert_eq!(Command::parse("gen 100").unwrap(), Command::Gen(100));
+        assert_eq!(
+            Command::parse("topology Klein").unwrap(),
+            Command::Topology(Some(Topology::Klein))
+        );
+        assert_eq!(
+            Command::parse(&format!("seed {}", u64::MAX)).unwrap(),
+            Command::Seed(Some(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn names_can_be_shortened_unless_ambiguous() {
+        assert_eq!(Command::parse("q").unwrap(), Command::Quit);
+        assert_eq!(Command::parse("sp 10").unwrap(), Command::Speed(Some(10)));
+        assert_eq!(Command::parse("seed").unwrap(), Command::Seed(None));
+
+        let error = Command::parse("s").unwrap_err().to_string();
+        assert_eq!(error, "Ambiguous command: s");
+    }
+
+    #[test]
+    fn rejects_bad_commands() {
+        let error = |line: &str| Command::parse(line).unwrap_err().to_string();
+
+        assert_eq!(error(""), "No command");
+        assert_eq!(error("fly"), "Not a command: fly");
+        assert_eq!(error("load"), "Usage: load <file>");
+        assert_eq!(error("rule B9/S"), "bad rule: B9/S");
+        assert_eq!(error("topology sphere"), "Unknown topology: sphere");
+        assert_eq!(error("gen 0"), "Generations must be 1 to 1000000");
+        assert_eq!(error("gen 1000001"), "Generations must be 1 to 1000000");
+        assert_eq!(error("gen -1"), "Generations must be a number: -1");
+        assert!(error("speed 99999999999999999999").starts_with("Speed must be a number"));
+        assert_eq!(error("speed 1001"), "Speed must be 1 to 1000");
+        assert_eq!(error("seed x"), "Seed must be a number: x");
+    }
+
+    #[test]
+    fn completes_names_and_arguments() {
+        assert_eq!(complete("s"), ["save ", "seed ", "speed "]);
+        assert_eq!(
+            complete("rule b36"),
+            ["rule B36/S23", "rule B3678/S34678", "rule B368/S245"]
+        );
+        assert_eq!(complete("topology c"), ["topology cylinder"]);
+        assert!(complete("gen 1").is_empty());
+    }
+
+    #[test]
+    fn edits_and_submits_the_line() {
+        let mut codes = chars("spede");
+        codes.extend([KeyCode::Backspace, KeyCode::Backspace]);
+        codes.extend(chars("ed"));
+
+        let (command_line, result) = type_keys(&codes, &[]);
+        assert_eq!(command_line.text(), "speed");
+        assert!(matches!(result, CommandLineResult::Editing));
+
+        codes.push(KeyCode::Enter);
+        let (_, result) = type_keys(&codes, &[]);
+        assert!(matches!(result, CommandLineResult::Submit(line) if line == "speed"));
+    }
+
+    #[test]
+    fn backspace_on_an_empty_line_cancels() {
+        let (_, result) = type_keys(&[KeyCode::Char('q'), KeyCode::Backspace], &[]);
+        assert!(matches!(result, CommandLineResult::Editing));
+
+        let (_, result) = type_keys(&[KeyCode::Backspace], &[]);
+        assert!(matches!(result, CommandLineResult::Cancel));
+    }
+
+    #[test]
+    fn tab_cycles_through_completions_and_back() {
+        let mut codes = chars("s");
+        codes.push(KeyCode::Tab);
+        assert_eq!(type_keys(&codes, &[]).0.text(), "save ");
+
+        codes.extend([KeyCode::Tab, KeyCode::Tab, KeyCode::Tab]);
+        assert_eq!(type_keys(&codes, &[]).0.text(), "s");
+
+        let mut codes = chars("s");
+        codes.push(KeyCode::BackTab);
+        assert_eq!(type_keys(&codes, &[]).0.text(), "speed ");
+    }
+
+    #[test]
+    fn up_and_down_browse_the_history() {
+        let history = ["gen 10".to_string(), "rule B3/S23".to_string()];
+        let mut codes = chars("sp");
+
+        codes.push(KeyCode::Up);
+        assert_eq!(type_keys(&codes, &history).0.text(), "rule B3/S23");
+
+        codes.extend([KeyCode::Up, KeyCode::Up]);
+        assert_eq!(type_keys(&codes, &history).0.text(), "gen 10");
+
+        codes.extend([KeyCode::Down, KeyCode::Down]);
+        assert_eq!(type_keys(&codes, &history).0.text(), "sp");
+    }
+}
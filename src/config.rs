@@ -0,0 +1,547 @@
+//! User configuration
+//!
+//! Settings are read from `rustlife/config.toml` in the [XDG config
+//! directory](https://specifications.freedesktop.org/basedir-spec/latest/), which is usually
+//! `~/.config`. A missing file, section, or setting just means the default. Everything is checked
+//! when the file is loaded, so a mistake is reported up front instead of being ignored.
+//!
+//! ```toml
+//! [defaults]
+//! rule = "B3/S23"
+//! topology = "torus"
+//! speed = 50
+//! density = 0.5
+//! symmetry = "C1"
+//!
+//! [appearance]
+//! theme = "classic"
+//! alive-glyph = "▓"
+//! alive-color = "#afffaf"
+//! gridlines = false
+//!
+//! [session]
+//! save = true
+//!
+//! [keys]
+//! quit = "Q"
+//! step = ["s", "Enter"]
+//! ```
+//!
+//! The settings window edits the same settings and saves them back to the file.
+use crate::{
+    command::MAX_SPEED,
+    keymap::{Action, Key, Keymap},
+    rule::{Rule, Topology},
+    soup::{Soup, Symmetry},
+    theme::{Theme, ThemeKind},
+    windows::KEY_CONTEXTS,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, env, fs, io, path::PathBuf};
+
+/// One key or a list of keys in the config file.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The `[defaults]` section as written.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct DefaultsSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    topology: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speed: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    density: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symmetry: Option<String>,
+}
+
+/// The `[appearance]` section as written.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct AppearanceSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    theme: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alive_glyph: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dead_glyph: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alive_color: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dead_color: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gridlines: Option<bool>,
+}
+
+/// The `[session]` section as written.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct SessionSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    save: Option<bool>,
+}
+
+/// The config file as written.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    defaults: DefaultsSection,
+
+    #[serde(default)]
+    appearance: AppearanceSection,
+
+    #[serde(default)]
+    session: SessionSection,
+
+    /// Keys for actions, by action name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    keys: BTreeMap<String, Keys>,
+}
+
+/// How cells and windows look.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Appearance {
+    /// Built-in theme to start with.
+    pub theme: ThemeKind,
+
+    /// Glyph for live cells instead of the theme's.
+    pub alive_glyph: Option<char>,
+
+    /// Glyph for dead cells instead of the theme's.
+    pub dead_glyph: Option<char>,
+
+    /// Color for live cells instead of the theme's.
+    pub alive_color: Option<(u8, u8, u8)>,
+
+    /// Color for dead cells instead of the theme's.
+    pub dead_color: Option<(u8, u8, u8)>,
+
+    /// True if gridlines are drawn between cells when zoomed in.
+    pub gridlines: bool,
+}
+
+impl Appearance {
+    /// Build a theme with the custom glyphs and colors on top.
+    pub fn theme(&self, kind: ThemeKind) -> Theme {
+        Theme::new(kind).with_cells(
+            self.alive_glyph,
+            self.dead_glyph,
+            self.alive_color,
+            self.dead_color,
+        )
+    }
+}
+
+impl Default for Appearance {
+    /// Create the default appearance, the classic theme as it is.
+    fn default() -> Self {
+        Self {
+            theme: ThemeKind::Classic,
+            alive_glyph: None,
+            dead_glyph: None,
+            alive_color: None,
+            dead_color: None,
+            gridlines: false,
+        }
+    }
+}
+
+/// User configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Rule to start with.
+    pub rule: Rule,
+
+    /// How the edges are joined to start with.
+    pub topology: Topology,
+
+    /// Generations per second when running.
+    pub speed: u32,
+
+    /// Settings for random soups.
+    pub soup: Soup,
+
+    /// How cells and windows look.
+    pub appearance: Appearance,
+
+    /// True if the universe is saved when quitting and put back when starting.
+    pub save_session: bool,
+
+    /// Key bindings.
+    pub keymap: Keymap,
+}
+
+impl Default for Config {
+    /// Create the default configuration.
+    fn default() -> Self {
+        Self {
+            rule: Rule::default(),
+            topology: Topology::Torus,
+            speed: 50,
+            soup: Soup::default(),
+            appearance: Appearance::default(),
+            save_session: true,
+            keymap: Keymap::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Get the path of the config file, if there's a home directory to put it in.
+    pub fn path() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::home_dir().map(|home| home.join(".config")))?;
+
+        Some(dir.join("rustlife").join("config.toml"))
+    }
+
+    /// Load the config file, or the defaults if there isn't one.
+    pub fn load() -> crate::Result<Config> {
+        let Some(path) = Config::path() else {
+            return Ok(Config::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                Config::parse(&text).map_err(|err| format!("{}: {err}", path.display()).into())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(format!("{}: {err}", path.display()).into()),
+        }
+    }
+
+    /// Save the config file, making its directory if needed.
+    ///
+    /// Returns the path it was saved to.
+    pub fn save(&self) -> crate::Result<PathBuf> {
+        let path = Config::path().ok_or("no home directory for the config file")?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+        }
+
+        fs::write(&path, self.to_toml()?).map_err(|err| format!("{}: {err}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Parse the text of a config file.
+    fn parse(text: &str) -> crate::Result<Config> {
+        let file: ConfigFile = toml::from_str(text)?;
+        let mut config = Config::default();
+
+        let defaults = file.defaults;
+
+        if let Some(rule) = defaults.rule {
+            config.rule = Rule::parse(&rule).map_err(|err| format!("[defaults] rule: {err}"))?;
+        }
+
+        if let Some(topology) = defaults.topology {
+            config.topology = Topology::from_name(&topology).ok_or_else(|| {
+                format!(
+                    "[defaults] topology: unknown topology {topology:?}, expected one of {}",
+                    names(Topology::ALL.iter().map(Topology::name))
+                )
+            })?;
+        }
+
+        if let Some(speed) = defaults.speed {
+            if !(1..=MAX_SPEED).contains(&speed) {
+                return Err(format!("[defaults] speed: must be 1 to {MAX_SPEED}").into());
+            }
+
+            config.speed = speed;
+        }
+
+        if let Some(density) = defaults.density {
+            if !(0.0..=1.0).contains(&density) {
+                return Err("[defaults] density: must be 0.0 to 1.0".into());
+            }
+
+            config.soup.density = density;
+        }
+
+        if let Some(symmetry) = defaults.symmetry {
+            config.soup.symmetry = Symmetry::from_name(&symmetry).ok_or_else(|| {
+                format!(
+                    "[defaults] symmetry: unknown symmetry {symmetry:?}, expected one of {}",
+                    names(Symmetry::ALL.iter().map(Symmetry::name))
+                )
+            })?;
+        }
+
+        let appearance = file.appearance;
+
+        if let Some(theme) = appearance.theme {
+            config.appearance.theme = ThemeKind::from_name(&theme).ok_or_else(|| {
+                format!(
+                    "[appearance] theme: unknown theme {theme:?}, expected one of {}",
+                    names(ThemeKind::ALL.iter().map(ThemeKind::name))
+                )
+            })?;
+        }
+
+        config.appearance.alive_glyph = appearance
+            .alive_glyph
+            .map(|glyph| {
+                parse_glyph(&glyph).map_err(|err| format!("[appearance] alive-glyph: {err}"))
+            })
+            .transpose()?;
+
+        config.appearance.dead_glyph = appearance
+            .dead_glyph
+            .map(|glyph| {
+                parse_glyph(&glyph).map_err(|err| format!("[appearance] dead-glyph: {err}"))
+            })
+            .transpose()?;
+
+        config.appearance.alive_color = appearance
+            .alive_color
+            .map(|color| {
+                parse_color(&color).map_err(|err| format!("[appearance] alive-color: {err}"))
+            })
+            .transpose()?;
+
+        config.appearance.dead_color = appearance
+            .dead_color
+            .map(|color| {
+                parse_color(&color).map_err(|err| format!("[appearance] dead-color: {err}"))
+            })
+            .transpose()?;
+
+        if let Some(gridlines) = appearance.gridlines {
+            config.appearance.gridlines = gridlines;
+        }
+
+        if let Some(save) = file.session.save {
+            config.save_session = save;
+        }
+
+        for (name, keys) in file.keys {
+            let action =
+                Action::from_name(&name).ok_or_else(|| format!("[keys] {name}: unknown action"))?;
+
+            let keys = match keys {
+                Keys::One(key) => vec![key],
+                Keys::Many(keys) => keys,
+            };
+
+            let keys = keys
+                .iter()
+                .map(|key| Key::parse(key))
+                .collect::<crate::Result<Vec<Key>>>()
+                .map_err(|err| format!("[keys] {name}: {err}"))?;
+
+            config.keymap.bind(action, keys);
+        }
+
+        for (context, actions) in KEY_CONTEXTS {
+            if let Some((first, second, key)) = config.keymap.clash(actions) {
+                return Err(format!(
+                    "[keys] {} and {} are both {} in {context}",
+                    first.name(),
+                    second.name(),
+                    key.name()
+                )
+                .into());
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Write the config as the text of a config file.
+    ///
+    /// Every default and appearance setting is written so they're easy to find and change, but
+    /// only keys that differ from the defaults are.
+    fn to_toml(&self) -> crate::Result<String> {
+        let default_keymap = Keymap::default();
+        let appearance = &self.appearance;
+
+        let file = ConfigFile {
+            defaults: DefaultsSection {
+                rule: Some(self.rule.to_string()),
+                topology: Some(self.topology.name().to_string()),
+                speed: Some(self.speed),
+                density: Some(self.soup.density),
+                symmetry: Some(self.soup.symmetry.name().to_string()),
+            },
+            appearance: AppearanceSection {
+                theme: Some(appearance.theme.name().to_string()),
+                alive_glyph: appearance.alive_glyph.map(String::from),
+                dead_glyph: appearance.dead_glyph.map(String::from),
+                alive_color: appearance.alive_color.map(format_color),
+                dead_color: appearance.dead_color.map(format_color),
+                gridlines: Some(appearance.gridlines),
+            },
+            session: SessionSection {
+                save: Some(self.save_session),
+            },
+            keys: Action::ALL
+                .iter()
+                .filter(|action| self.keymap.keys(**action) != default_keymap.keys(**action))
+                .map(|action| {
+                    let keys = self.keymap.keys(*action).iter().map(Key::name).collect();
+                    (action.name().to_string(), Keys::Many(keys))
+                })
+                .collect(),
+        };
+
+        Ok(toml::to_string(&file)?)
+    }
+}
+
+/// Parse a glyph, which has to be a single character.
+fn parse_glyph(text: &str) -> crate::Result<char> {
+    let mut chars = text.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_control() => Ok(c),
+        _ => Err(format!("{text:?} isn't a single character").into()),
+    }
+}
+
+/// Parse a color written as `#rrggbb`.
+fn parse_color(text: &str) -> crate::Result<(u8, u8, u8)> {
+    let hex = text
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("{text:?} isn't a color like \"#ff8800\""))?;
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+
+    Ok((channel(0), channel(2), channel(4)))
+}
+
+/// Write a color as `#rrggbb`.
+fn format_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// List names for an error message.
+fn names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    names.collect::<Vec<&str>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_file_is_the_defaults() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn settings_are_read() {
+        let config = Config::parse(
+            r##"
+            [defaults]
+            rule = "B36/S23"
+            topology = "cylinder"
+            speed = 20
+            density = 0.25
+            symmetry = "D4"
+
+            [appearance]
+            theme = "amber"
+            alive-glyph = "@"
+            alive-color = "#ff8800"
+            gridlines = true
+
+            [session]
+            save = false
+
+            [keys]
+            quit = "Q"
+            step = ["s", "Enter"]
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.rule, Rule::parse("B36/S23").unwrap());
+        assert_eq!(config.topology, Topology::Cylinder);
+        assert_eq!(config.speed, 20);
+        assert_eq!(config.soup.density, 0.25);
+        assert_eq!(config.soup.symmetry, Symmetry::D4);
+        assert_eq!(config.appearance.theme, ThemeKind::Amber);
+        assert_eq!(config.appearance.alive_glyph, Some('@'));
+        assert_eq!(config.appearance.dead_glyph, None);
+        assert_eq!(config.appearance.alive_color, Some((0xff, 0x88, 0x00)));
+        assert!(config.appearance.gridlines);
+        assert!(!config.save_session);
+        assert_eq!(config.keymap.keys(Action::Quit), [Key::parse("Q").unwrap()]);
+        assert_eq!(
+            config.keymap.keys(Action::Step),
+            [Key::parse("s").unwrap(), Key::parse("Enter").unwrap()]
+        );
+    }
+
+    #[test]
+    fn mistakes_are_reported_with_where_they_are() {
+        let error = |text: &str| Config::parse(text).unwrap_err().to_string();
+
+        assert!(error("[defaults]\nrule = \"B9\"").starts_with("[defaults] rule:"));
+        assert!(error("[defaults]\ntopology = \"sphere\"").starts_with("[defaults] topology:"));
+        assert!(error("[defaults]\nspeed = 0").starts_with("[defaults] speed:"));
+        assert!(error("[defaults]\ndensity = 1.5").starts_with("[defaults] density:"));
+        assert!(
+            error("[appearance]\nalive-glyph = \"ab\"").starts_with("[appearance] alive-glyph:")
+        );
+        assert!(
+            error("[appearance]\ndead-color = \"red\"").starts_with("[appearance] dead-color:")
+        );
+        assert!(error("[keys]\nfly = \"F\"").starts_with("[keys] fly: unknown action"));
+    }
+
+    #[test]
+    fn keys_bound_twice_are_reported() {
+        let error = Config::parse("[keys]\npan-up = \"j\"")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "[keys] move-down and pan-up are both j in normal mode"
+        );
+
+        let error = Config::parse("[keys]\nnext-shape = \"Enter\"").unwrap_err();
+        assert!(error.to_string().ends_with("in draw mode"));
+
+        // Keys only clash with actions that are looked up at the same time
+        assert!(Config::parse("[keys]\nrotate-cw = \"J\"").is_ok());
+    }
+
+    #[test]
+    fn written_config_reads_back_the_same() {
+        let mut config = Config {
+            topology: Topology::Klein,
+            ..Config::default()
+        };
+        config.appearance.dead_color = Some((1, 2, 3));
+        config
+            .keymap
+            .bind(Action::Run, vec![Key::parse("Ctrl+r").unwrap()]);
+
+        assert_eq!(Config::parse(&config.to_toml().unwrap()).unwrap(), config);
+    }
+}
@@ -0,0 +1,515 @@
+//! Key bindings
+//!
+//! Every key the Life window responds to is bound to an [`Action`] in a [`Keymap`]. The defaults
+//! can be changed in the `[keys]` section of the config file, and the help window is made from the
+//! keymap so it always shows the keys that actually work.
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+
+/// Name, description, help section, and default keys of an action.
+type ActionInfo = (
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static [&'static str],
+);
+
+/// Something a key can do in the Life window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    MoveUpLeft,
+    MoveUpRight,
+    MoveDownLeft,
+    MoveDownRight,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    PanUpLeft,
+    PanUpRight,
+    PanDownLeft,
+    PanDownRight,
+    CenterOnCursor,
+    FitPattern,
+    ZoomIn,
+    ZoomOut,
+    ToggleGridlines,
+    CycleColoring,
+    CycleTheme,
+    Graph,
+    Period,
+    Census,
+    Track,
+    Info,
+    NextGeneration,
+    Step,
+    Run,
+    ToggleCell,
+    Clear,
+    RandomSoup,
+    SoupSettings,
+    Save,
+    CommandLine,
+    Select,
+    Paste,
+    Draw,
+    Fill,
+    Library,
+    Copy,
+    Cut,
+    ClearSelection,
+    ClearOutside,
+    RotateCw,
+    RotateCcw,
+    Rotate180,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    PasteMode,
+    Place,
+    NextShape,
+    Cancel,
+    Help,
+    Settings,
+    About,
+    Quit,
+}
+
+impl Action {
+    /// All the actions, in the order they're shown in the help.
+    pub const ALL: [Action; 60] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUpLeft,
+        Action::MoveUpRight,
+        Action::MoveDownLeft,
+        Action::MoveDownRight,
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::PanUpLeft,
+        Action::PanUpRight,
+        Action::PanDownLeft,
+        Action::PanDownRight,
+        Action::CenterOnCursor,
+        Action::FitPattern,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ToggleGridlines,
+        Action::CycleColoring,
+        Action::CycleTheme,
+        Action::Graph,
+        Action::Period,
+        Action::Census,
+        Action::Track,
+        Action::Info,
+        Action::NextGeneration,
+        Action::Step,
+        Action::Run,
+        Action::ToggleCell,
+        Action::Clear,
+        Action::RandomSoup,
+        Action::SoupSettings,
+        Action::Save,
+        Action::CommandLine,
+        Action::Select,
+        Action::Paste,
+        Action::Draw,
+        Action::Fill,
+        Action::Library,
+        Action::Copy,
+        Action::Cut,
+        Action::ClearSelection,
+        Action::ClearOutside,
+        Action::RotateCw,
+        Action::RotateCcw,
+        Action::Rotate180,
+        Action::FlipHorizontal,
+        Action::FlipVertical,
+        Action::Transpose,
+        Action::PasteMode,
+        Action::Place,
+        Action::NextShape,
+        Action::Cancel,
+        Action::Help,
+        Action::Settings,
+        Action::About,
+        Action::Quit,
+    ];
+
+    /// Get the name of the action in the config file.
+    pub fn name(&self) -> &'static str {
+        self.info().0
+    }
+
+    /// Get a short description of the action for the help.
+    pub fn description(&self) -> &'static str {
+        self.info().1
+    }
+
+    /// Get the heading the action is under in the help.
+    pub fn section(&self) -> &'static str {
+        self.info().2
+    }
+
+    /// Get the keys the action is bound to unless the config file changes them.
+    fn default_keys(&self) -> &'static [&'static str] {
+        self.info().3
+    }
+
+    /// Look up an action by its name in the config file.
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// Get the name, description, help section, and default keys of the action.
+    fn info(&self) -> ActionInfo {
+        const MOVE: &str = "Moving";
+        const VIEW: &str = "View";
+        const EDIT: &str = "Editing";
+        const MODES: &str = "Selecting, pasting, and drawing";
+        const OTHER: &str = "Other";
+
+        match self {
+            Action::MoveUp => ("move-up", "Move up", MOVE, &["k", "Up"]),
+            Action::MoveDown => ("move-down", "Move down", MOVE, &["j", "Down"]),
+            Action::MoveLeft => ("move-left", "Move left", MOVE, &["h", "Left"]),
+            Action::MoveRight => ("move-right", "Move right", MOVE, &["l", "Right"]),
+            Action::MoveUpLeft => ("move-up-left", "Move up left", MOVE, &["y"]),
+            Action::MoveUpRight => ("move-up-right", "Move up right", MOVE, &["u"]),
+            Action::MoveDownLeft => ("move-down-left", "Move down left", MOVE, &["b"]),
+            Action::MoveDownRight => ("move-down-right", "Move down right", MOVE, &["n"]),
+            Action::PanUp => ("pan-up", "Pan up", MOVE, &["K", "Shift+Up"]),
+            Action::PanDown => ("pan-down", "Pan down", MOVE, &["J", "Shift+Down"]),
+            Action::PanLeft => ("pan-left", "Pan left", MOVE, &["H", "Shift+Left"]),
+            Action::PanRight => ("pan-right", "Pan right", MOVE, &["L", "Shift+Right"]),
+            Action::PanUpLeft => ("pan-up-left", "Pan up left", MOVE, &["Y"]),
+            Action::PanUpRight => ("pan-up-right", "Pan up right", MOVE, &["U"]),
+            Action::PanDownLeft => ("pan-down-left", "Pan down left", MOVE, &["B"]),
+            Action::PanDownRight => ("pan-down-right", "Pan down right", MOVE, &["N"]),
+            Action::CenterOnCursor => ("center", "Center on cursor", VIEW, &["z"]),
+            Action::FitPattern => ("fit", "Fit pattern", VIEW, &["Z"]),
+            Action::ZoomIn => ("zoom-in", "Zoom in", VIEW, &["+", "="]),
+            Action::ZoomOut => ("zoom-out", "Zoom out", VIEW, &["-"]),
+            Action::ToggleGridlines => ("gridlines", "Toggle gridlines", VIEW, &["g"]),
+            Action::CycleColoring => ("coloring", "Age colors", VIEW, &["A"]),
+            Action::CycleTheme => ("theme", "Cycle color themes", VIEW, &["M"]),
+            Action::Graph => ("graph", "Population graph", VIEW, &["G"]),
+            Action::Period => ("period", "Period of pattern or selection", VIEW, &["P"]),
+            Action::Census => ("census", "Census of objects", VIEW, &["C"]),
+            Action::Track => ("track", "Track spaceship in selection", VIEW, &["T"]),
+            Action::Info => ("info", "Info overlay", VIEW, &["I"]),
+            Action::NextGeneration => ("next-generation", "Next generation preview", VIEW, &["E"]),
+            Action::Step => ("step", "Step", EDIT, &["s"]),
+            Action::Run => ("run", "Run start/stop", EDIT, &["r"]),
+            Action::ToggleCell => ("toggle", "Toggle cell", EDIT, &["t", "Space"]),
+            Action::Clear => ("clear", "Clear screen", EDIT, &["c"]),
+            Action::RandomSoup => ("random-soup", "Random soup", EDIT, &["R"]),
+            Action::SoupSettings => ("soup-settings", "Soup settings", EDIT, &["S"]),
+            Action::Save => ("save", "Save RLE", EDIT, &["W"]),
+            Action::CommandLine => ("command-line", "Command line", EDIT, &[":"]),
+            Action::Select => ("select", "Select", MODES, &["v"]),
+            Action::Paste => ("paste", "Paste", MODES, &["p"]),
+            Action::Draw => ("draw", "Draw shapes", MODES, &["d"]),
+            Action::Fill => ("fill", "Flood fill", MODES, &["f"]),
+            Action::Library => ("library", "Pattern library", MODES, &["i"]),
+            Action::Copy => ("copy", "Copy selection", MODES, &["c"]),
+            Action::Cut => ("cut", "Cut selection", MODES, &["x"]),
+            Action::ClearSelection => ("clear-selection", "Clear selection", MODES, &["d"]),
+            Action::ClearOutside => ("clear-outside", "Clear outside", MODES, &["D"]),
+            Action::RotateCw => ("rotate-cw", "Rotate clockwise", MODES, &[">"]),
+            Action::RotateCcw => ("rotate-ccw", "Rotate counterclockwise", MODES, &["<"]),
+            Action::Rotate180 => ("rotate-180", "Rotate 180°", MODES, &["^"]),
+            Action::FlipHorizontal => ("flip-horizontal", "Flip left to right", MODES, &["|"]),
+            Action::FlipVertical => ("flip-vertical", "Flip top to bottom", MODES, &["_"]),
+            Action::Transpose => ("transpose", "Transpose", MODES, &["\\"]),
+            Action::PasteMode => ("paste-mode", "Cycle paste mode", MODES, &["o"]),
+            Action::Place => ("place", "Paste or draw here", MODES, &["Enter"]),
+            Action::NextShape => ("next-shape", "Next shape", MODES, &["Tab"]),
+            Action::Cancel => ("cancel", "Cancel", MODES, &["Esc"]),
+            Action::Help => ("help", "Help", OTHER, &["?"]),
+            Action::Settings => ("settings", "Settings", OTHER, &["O"]),
+            Action::About => ("about", "About", OTHER, &["a"]),
+            Action::Quit => ("quit", "Quit", OTHER, &["q", "Esc"]),
+        }
+    }
+}
+
+/// Names of keys that aren't a single character, as used in the config file.
+const KEY_NAMES: [(&str, KeyCode); 16] = [
+    ("Space", KeyCode::Char(' ')),
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+];
+
+/// Modifier prefixes, as used in the config file.
+const MODIFIER_NAMES: [(&str, KeyModifiers); 3] = [
+    ("Shift+", KeyModifiers::SHIFT),
+    ("Ctrl+", KeyModifiers::CONTROL),
+    ("Alt+", KeyModifiers::ALT),
+];
+
+/// A key with modifiers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Make a key, ignoring modifiers that don't matter for it.
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::SHIFT | KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+        // Shift is already part of the character, and terminals don't agree on reporting it
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers -= KeyModifiers::SHIFT;
+        }
+
+        Self { code, modifiers }
+    }
+
+    /// Parse a key like `q`, `Space`, `Shift+Up`, `Ctrl+u`, or `F5`.
+    pub fn parse(text: &str) -> crate::Result<Key> {
+        let mut rest = text;
+        let mut modifiers = KeyModifiers::NONE;
+
+        // Take modifiers off the front, leaving at least one character so `+` is a key
+        while let Some((prefix, modifier)) = MODIFIER_NAMES.iter().find(|(prefix, _)| {
+            rest.len() > prefix.len()
+                && rest
+                    .get(..prefix.len())
+                    .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        }) {
+            rest = &rest[prefix.len()..];
+            modifiers |= *modifier;
+        }
+
+        let mut chars = rest.chars();
+
+        let code = match (chars.next(), chars.next()) {
+            // Shift is part of the character, so `Shift+a` means `A`
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                if !c.is_ascii_alphabetic() {
+                    return Err(format!("write {text} as the shifted character").into());
+                }
+
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => KEY_NAMES
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+                .map(|(_, code)| *code)
+                .or_else(|| {
+                    let n = rest.strip_prefix(['F', 'f'])?.parse().ok()?;
+                    (1..=12).contains(&n).then_some(KeyCode::F(n))
+                })
+                .ok_or_else(|| format!("unknown key: {text}"))?,
+        };
+
+        Ok(Key::new(code, modifiers))
+    }
+
+    /// Check if a key event is this key.
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        *self == Key::new(key_event.code, key_event.modifiers)
+    }
+
+    /// Get the key the way it's written in the config file, so it can be parsed back.
+    pub fn name(&self) -> String {
+        let key = match self.code {
+            KeyCode::F(n) => format!("F{n}"),
+            KeyCode::Char(c) if c != ' ' => c.to_string(),
+            code => KEY_NAMES
+                .iter()
+                .find(|(_, named)| *named == code)
+                .map_or("?", |(name, _)| name)
+                .to_string(),
+        };
+
+        self.modifier_prefix() + &key
+    }
+
+    /// Get the modifier prefixes, like `Ctrl+`.
+    fn modifier_prefix(&self) -> String {
+        MODIFIER_NAMES
+            .iter()
+            .filter(|(_, modifier)| self.modifiers.contains(*modifier))
+            .map(|(prefix, _)| *prefix)
+            .collect()
+    }
+}
+
+impl fmt::Display for Key {
+    /// Write the key the way the help shows it, with arrows for the arrow keys.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arrow = match self.code {
+            KeyCode::Up => "↑",
+            KeyCode::Down => "↓",
+            KeyCode::Left => "←",
+            KeyCode::Right => "→",
+            _ => return write!(f, "{}", self.name()),
+        };
+
+        write!(f, "{}{arrow}", self.modifier_prefix())
+    }
+}
+
+/// The keys bound to each action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    /// Keys for each action, in the same order as [`Action::ALL`].
+    keys: Vec<Vec<Key>>,
+}
+
+impl Keymap {
+    /// Get the keys bound to an action.
+    pub fn keys(&self, action: Action) -> &[Key] {
+        &self.keys[Action::ALL.iter().position(|a| *a == action).unwrap()]
+    }
+
+    /// Replace the keys bound to an action.
+    pub fn bind(&mut self, action: Action, keys: Vec<Key>) {
+        self.keys[Action::ALL.iter().position(|a| *a == action).unwrap()] = keys;
+    }
+
+    /// Find two of some actions that share a key, and the key.
+    pub fn clash(&self, actions: &[Action]) -> Option<(Action, Action, Key)> {
+        actions.iter().enumerate().find_map(|(i, first)| {
+            actions[i + 1..].iter().find_map(|second| {
+                self.keys(*first)
+                    .iter()
+                    .find(|key| self.keys(*second).contains(key))
+                    .map(|key| (*first, *second, *key))
+            })
+        })
+    }
+
+    /// Find which of some actions a key event is bound to.
+    ///
+    /// The same key can do different things in different modes, so only the actions that make
+    /// sense right now are looked at. The first one that matches wins.
+    pub fn action(&self, key_event: &KeyEvent, actions: &[Action]) -> Option<Action> {
+        actions
+            .iter()
+            .copied()
+            .find(|action| self.keys(*action).iter().any(|key| key.matches(key_event)))
+    }
+}
+
+impl Default for Keymap {
+    /// Create the default keymap.
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL
+                .iter()
+                .map(|action| {
+                    action
+                        .default_keys()
+                        .iter()
+                        .map(|key| Key::parse(key).expect("default keys are valid"))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a key event like a terminal would send.
+    fn event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_keys_and_modifiers() {
+        let key = Key::parse("Ctrl+u").unwrap();
+        assert!(key.matches(&event(KeyCode::Char('u'), KeyModifiers::CONTROL)));
+        assert!(!key.matches(&event(KeyCode::Char('u'), KeyModifiers::NONE)));
+
+        let key = Key::parse("shift+alt+up").unwrap();
+        assert!(key.matches(&event(KeyCode::Up, KeyModifiers::SHIFT | KeyModifiers::ALT)));
+
+        assert_eq!(Key::parse("F5").unwrap().code, KeyCode::F(5));
+        assert_eq!(Key::parse("Space").unwrap().code, KeyCode::Char(' '));
+    }
+
+    #[test]
+    fn plus_can_be_a_key() {
+        assert_eq!(Key::parse("+").unwrap().code, KeyCode::Char('+'));
+
+        let key = Key::parse("Ctrl++").unwrap();
+        assert_eq!(key.code, KeyCode::Char('+'));
+        assert_eq!(key.modifiers, KeyModifiers::CONTROL);
+    }
+
+    #[test]
+    fn shift_with_a_letter_is_the_capital() {
+        let key = Key::parse("Shift+a").unwrap();
+
+        assert_eq!(key, Key::parse("A").unwrap());
+        assert!(key.matches(&event(KeyCode::Char('A'), KeyModifiers::SHIFT)));
+        assert!(key.matches(&event(KeyCode::Char('A'), KeyModifiers::NONE)));
+        assert!(Key::parse("Shift+1").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(Key::parse("").is_err());
+        assert!(Key::parse("Ctrl+").is_err());
+        assert!(Key::parse("Hyper+x").is_err());
+        assert!(Key::parse("F13").is_err());
+        assert!(Key::parse("F0").is_err());
+        assert!(Key::parse("Ctrlé").is_err());
+        assert!(Key::parse("Shiftéé").is_err());
+        assert_eq!(Key::parse("Alt+é").unwrap().code, KeyCode::Char('é'));
+    }
+
+    #[test]
+    fn names_parse_back() {
+        let keymap = Keymap::default();
+
+        for action in Action::ALL {
+            for key in keymap.keys(action) {
+                assert_eq!(Key::parse(&key.name()).unwrap(), *key, "{}", key.name());
+            }
+        }
+    }
+
+    #[test]
+    fn first_matching_action_wins() {
+        let keymap = Keymap::default();
+        let key_event = event(KeyCode::Esc, KeyModifiers::NONE);
+
+        assert_eq!(
+            keymap.action(&key_event, &[Action::Cancel, Action::Quit]),
+            Some(Action::Cancel)
+        );
+        assert_eq!(keymap.action(&key_event, &[Action::Help]), None);
+    }
+}
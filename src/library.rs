@@ -0,0 +1,170 @@
+//! Built-in pattern library
+//!
+//! Classic patterns, stored as RLE and grouped by category.
+use crate::pattern::Pattern;
+
+/// A named pattern in the library.
+pub struct LibraryPattern {
+    /// Name of the pattern.
+    pub name: &'static str,
+
+    /// What kind of pattern it is.
+    pub category: &'static str,
+
+    /// The cells as RLE.
+    rle: &'static str,
+}
+
+impl LibraryPattern {
+    /// Get the cells of the pattern.
+    pub fn pattern(&self) -> Pattern {
+        Pattern::from_rle(self.rle).expect("built-in patterns are valid RLE")
+    }
+}
+
+/// All the patterns, with each category together.
+pub const LIBRARY: &[LibraryPattern] = &[
+    LibraryPattern {
+        name: "Block",
+        category: "Still lifes",
+        rle: "x = 2, y = 2\n2o$2o!",
+    },
+    LibraryPattern {
+        name: "Beehive",
+        category: "Still lifes",
+        rle: "x = 4, y = 3\nb2o$o2bo$b2o!",
+    },
+    LibraryPattern {
+        name: "Loaf",
+        category: "Still lifes",
+        rle: "x = 4, y = 4\nb2o$o2bo$bobo$2bo!",
+    },
+    LibraryPattern {
+        name: "Boat",
+        category: "Still lifes",
+        rle: "x = 3, y = 3\n2o$obo$bo!",
+    },
+    LibraryPattern {
+        name: "Ship",
+        category: "Still lifes",
+        rle: "x = 3, y = 3\n2o$obo$b2o!",
+    },
+    LibraryPattern {
+        name: "Tub",
+        category: "Still lifes",
+        rle: "x = 3, y = 3\nbo$obo$bo!",
+    },
+    LibraryPattern {
+        name: "Pond",
+        category: "Still lifes",
+        rle: "x = 4, y = 4\nb2o$o2bo$o2bo$b2o!",
+    },
+    LibraryPattern {
+        name: "Long boat",
+        category: "Still lifes",
+        rle: "x = 4, y = 4\n2o$obo$bobo$2bo!",
+    },
+    LibraryPattern {
+        name: "Barge",
+        category: "Still lifes",
+        rle: "x = 4, y = 4\nbo$obo$bobo$2bo!",
+    },
+    LibraryPattern {
+        name: "Mango",
+        category: "Still lifes",
+        rle: "x = 5, y = 4\nb2o$o2bo$bo2bo$2b2o!",
+    },
+    LibraryPattern {
+        name: "Aircraft carrier",
+        category: "Still lifes",
+        rle: "x = 4, y = 3\n2o$o2bo$2b2o!",
+    },
+    LibraryPattern {
+        name: "Snake",
+        category: "Still lifes",
+        rle: "x = 4, y = 2\n2obo$ob2o!",
+    },
+    LibraryPattern {
+        name: "Eater 1",
+        category: "Still lifes",
+        rle: "x = 4, y = 4\n2o$obo$2bo$2b2o!",
+    },
+    LibraryPattern {
+        name: "Blinker",
+        category: "Oscillators",
+        rle: "x = 3, y = 1\n3o!",
+    },
+    LibraryPattern {
+        name: "Toad",
+        category: "Oscillators",
+        rle: "x = 4, y = 2\nb3o$3o!",
+    },
+    LibraryPattern {
+        name: "Beacon",
+        category: "Oscillators",
+        rle: "x = 4, y = 4\n2o$2o$2b2o$2b2o!",
+    },
+    LibraryPattern {
+        name: "Clock",
+        category: "Oscillators",
+        rle: "x = 4, y = 4\n2bo$obo$bobo$bo!",
+    },
+    LibraryPattern {
+        name: "Pulsar",
+        category: "Oscillators",
+        rle: "x = 13, y = 13\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+              o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    },
+    LibraryPattern {
+        name: "Pentadecathlon",
+        category: "Oscillators",
+        rle: "x = 10, y = 3\n2bo4bo$2ob4ob2o$2bo4bo!",
+    },
+    LibraryPattern {
+        name: "Glider",
+        category: "Spaceships",
+        rle: "x = 3, y = 3\nbo$2bo$3o!",
+    },
+    LibraryPattern {
+        name: "LWSS",
+        category: "Spaceships",
+        rle: "x = 5, y = 4\nbo2bo$o$o3bo$4o!",
+    },
+    LibraryPattern {
+        name: "MWSS",
+        category: "Spaceships",
+        rle: "x = 6, y = 5\n3bo$bo3bo$o$o4bo$5o!",
+    },
+    LibraryPattern {
+        name: "HWSS",
+        category: "Spaceships",
+        rle: "x = 7, y = 5\n3b2o$bo4bo$o$o5bo$6o!",
+    },
+    LibraryPattern {
+        name: "Gosper glider gun",
+        category: "Guns",
+        rle: "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+              2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    },
+    LibraryPattern {
+        name: "Simkin glider gun",
+        category: "Guns",
+        rle: "x = 33, y = 21\n2o5b2o$2o5b2o2$4b2o$4b2o5$22b2ob2o$21bo5bo$21bo6bo2b2o$\
+              21b3o3bo3b2o$26bo4$20b2o$20bo$21b3o$23bo!",
+    },
+    LibraryPattern {
+        name: "R-pentomino",
+        category: "Methuselahs",
+        rle: "x = 3, y = 3\nb2o$2o$bo!",
+    },
+    LibraryPattern {
+        name: "Acorn",
+        category: "Methuselahs",
+        rle: "x = 7, y = 3\nbo$3bo$2o2b3o!",
+    },
+    LibraryPattern {
+        name: "Diehard",
+        category: "Methuselahs",
+        rle: "x = 8, y = 3\n6bo$2o$bo3b3o!",
+    },
+];
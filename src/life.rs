@@ -2,7 +2,14 @@
 //!
 //! This Module implements the state management for Conway's Life. The implementation is a naive
 //! grid.
-use rand::random;
+use crate::{
+    pattern::{PasteMode, Pattern, Transform},
+    period::{Fingerprint, Period},
+    rule::{Rule, Topology},
+    shape,
+    soup::Soup,
+};
+use std::collections::VecDeque;
 
 /// Whether a cell is alive or dead.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -11,12 +18,86 @@ pub enum LifeCell {
     Alive,
 }
 
+/// How a cell changes from one generation to the next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    /// Stays alive or stays dead.
+    Same,
+
+    /// Comes alive.
+    Birth,
+
+    /// Dies.
+    Death,
+}
+
+/// Age of a cell that has never changed state.
+pub const AGE_UNKNOWN: u16 = u16::MAX;
+
+/// Most generations of statistics to keep.
+const HISTORY_LENGTH: usize = 100_000;
+
+/// Most generations of fingerprints to keep, which is the longest period that can be found.
+const FINGERPRINT_LENGTH: usize = 1000;
+
+/// Statistics for one generation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Generation the statistics are for.
+    pub generation: u64,
+
+    /// Number of live cells.
+    pub population: usize,
+
+    /// Cells that came alive since the generation before.
+    pub births: usize,
+
+    /// Cells that died since the generation before.
+    pub deaths: usize,
+}
+
+/// Offsets of the eight cells around a cell.
+pub const NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
 /// Represents a Life grid.
 pub struct Life {
     width: usize,
     height: usize,
     cells: [Vec<Vec<LifeCell>>; 2],
     cur_page: usize,
+
+    /// Rule the cells follow.
+    rule: Rule,
+
+    /// How the edges of the grid are joined.
+    topology: Topology,
+
+    /// Number of generations each cell has been in its current state, if tracking ages.
+    ages: Option<Vec<Vec<u16>>>,
+
+    /// Generations stepped since the grid was started or cleared.
+    generation: u64,
+
+    /// Statistics for recent generations, oldest first.
+    history: VecDeque<Stats>,
+
+    /// Fingerprints of the generations since the cells were last edited, oldest first.
+    fingerprints: VecDeque<Fingerprint>,
+
+    /// Region to look for a period in as `(x, y, width, height)`, or `None` for the whole grid.
+    watched: Option<(usize, usize, usize, usize)>,
+
+    /// Number of times cells have been changed other than by stepping.
+    edits: u64,
 }
 
 impl Life {
@@ -27,9 +108,123 @@ impl Life {
             height: 0,
             cells: [Vec::new(), Vec::new()],
             cur_page: 0,
+            rule: Rule::default(),
+            topology: Topology::Torus,
+            ages: None,
+            generation: 0,
+            history: VecDeque::new(),
+            fingerprints: VecDeque::new(),
+            watched: None,
+            edits: 0,
         }
     }
 
+    /// Get the rule the cells follow.
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Set the rule the cells follow.
+    ///
+    /// Generations under the old rule say nothing about the new one, so looking for a period
+    /// starts over.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.fingerprints.clear();
+    }
+
+    /// Get how the edges of the grid are joined.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Set how the edges of the grid are joined.
+    ///
+    /// Looking for a period starts over, like when the rule changes.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.fingerprints.clear();
+    }
+
+    /// Get the number of times cells have been changed other than by stepping, to tell if the
+    /// grid was edited since some earlier time.
+    pub fn edits(&self) -> u64 {
+        self.edits
+    }
+
+    /// Get the number of generations stepped since the grid was started or cleared.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Set the generation count, like when carrying on from a saved session.
+    ///
+    /// The history starts over from here.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+        self.start_history();
+    }
+
+    /// Get the statistics for recent generations, oldest first.
+    pub fn history(&self) -> &VecDeque<Stats> {
+        &self.history
+    }
+
+    /// Forget the history and start it again with the current generation.
+    fn start_history(&mut self) {
+        self.fingerprints.clear();
+        self.history.clear();
+        self.history.push_back(Stats {
+            generation: self.generation,
+            population: self.population(),
+            births: 0,
+            deaths: 0,
+        });
+    }
+
+    /// Get the region being looked at for a period, or `None` if it's the whole grid.
+    pub fn watched(&self) -> Option<(usize, usize, usize, usize)> {
+        self.watched
+    }
+
+    /// Look for a period in a region instead of the whole grid, or the whole grid again if `None`.
+    pub fn watch(&mut self, region: Option<(usize, usize, usize, usize)>) {
+        self.watched = region;
+        self.fingerprints.clear();
+    }
+
+    /// Find out how the cells repeat, if they've started to.
+    pub fn period(&self) -> Option<Period> {
+        let wrap = match (self.watched, self.topology) {
+            (Some(_), _) | (None, Topology::Plane) => None,
+            _ => Some((self.width, self.height)),
+        };
+
+        Period::detect(&self.fingerprints, wrap)
+    }
+
+    /// Get the fingerprint of the cells being looked at for a period.
+    fn fingerprint(&self) -> Fingerprint {
+        let (x, y, width, height) = self
+            .watched
+            .or_else(|| self.bounding_box())
+            .unwrap_or((0, 0, 0, 0));
+
+        Fingerprint::new(
+            self.generation,
+            &self.copy_region(x, y, width, height),
+            (x, y),
+        )
+    }
+
+    /// Count the live cells.
+    pub fn population(&self) -> usize {
+        self.cells[self.cur_page]
+            .iter()
+            .map(|row| row.iter().filter(|cell| **cell == LifeCell::Alive).count())
+            .sum()
+    }
+
     /// Initialize a Life object to a given width and height.
     pub fn init(&mut self, width: usize, height: usize) {
         self.cells[0].clear();
@@ -37,32 +232,74 @@ impl Life {
         self.width = width;
         self.height = height;
         self.cur_page = 0;
+        self.generation = 0;
+        self.watched = None;
 
         for _ in 0..height {
             self.cells[0].push(vec![LifeCell::Dead; width]);
             self.cells[1].push(vec![LifeCell::Dead; width]);
         }
+
+        self.reset_ages();
+        self.start_history();
     }
 
-    /// Fill the grid with random values.
-    pub fn randomize(&mut self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let v: f32 = random();
-
-                self.set_cell(
-                    x,
-                    y,
-                    if v < 0.5 {
-                        LifeCell::Alive
-                    } else {
-                        LifeCell::Dead
-                    },
-                );
-            }
+    /// Turn cell age tracking on or off.
+    ///
+    /// Turning it on starts all the current cells at age `0`.
+    pub fn set_track_ages(&mut self, track: bool) {
+        if track != self.ages.is_some() {
+            self.ages = track.then(Vec::new);
+            self.reset_ages();
+        }
+    }
+
+    /// Reset all the ages, if tracking them.
+    ///
+    /// Live cells start at `0`. Dead cells start at [`AGE_UNKNOWN`] so they don't look like they
+    /// just died.
+    fn reset_ages(&mut self) {
+        if let Some(ages) = self.ages.as_mut() {
+            *ages = self.cells[self.cur_page]
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| match cell {
+                            LifeCell::Alive => 0,
+                            LifeCell::Dead => AGE_UNKNOWN,
+                        })
+                        .collect()
+                })
+                .collect();
         }
     }
 
+    /// Get a reference to all the cell ages, if tracking them.
+    ///
+    /// This is the number of generations the cell has been alive, or if it's dead, the number of
+    /// generations since it died.
+    pub fn get_ages(&self) -> Option<&Vec<Vec<u16>>> {
+        self.ages.as_ref()
+    }
+
+    /// Fill a region with a random soup made from a seed.
+    ///
+    /// The region wraps around the edges of the grid.
+    pub fn randomize(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        soup: &Soup,
+        seed: u64,
+    ) {
+        let width = width.min(self.width);
+        let height = height.min(self.height);
+
+        self.paste(&soup.pattern(width, height, seed), x, y, PasteMode::Copy);
+    }
+
     /// Get the status of a cell.
     pub fn get_cell(&self, x: usize, y: usize) -> LifeCell {
         if x < self.width && y < self.height {
@@ -73,7 +310,7 @@ impl Life {
     }
 
     /// Get the weight of a cell, `1` for alive, `0` for dead.
-    fn get_cell_weight(&self, x: usize, y: usize) -> i32 {
+    fn get_cell_weight(&self, x: usize, y: usize) -> usize {
         if x < self.width && y < self.height {
             if self.cells[self.cur_page][y][x] == LifeCell::Alive {
                 1
@@ -85,10 +322,64 @@ impl Life {
         }
     }
 
+    /// Count the live neighbors of a cell, following how the edges are joined.
+    pub fn neighbor_count(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+
+        for (dx, dy) in NEIGHBORS {
+            if let Some((nx, ny)) = self
+                .topology
+                .neighbor(x, y, dx, dy, self.width, self.height)
+            {
+                count += self.get_cell_weight(nx, ny);
+            }
+        }
+
+        count
+    }
+
+    /// Get what a cell will be in the next generation.
+    pub fn next_cell(&self, x: usize, y: usize) -> LifeCell {
+        let alive = self.get_cell(x, y) == LifeCell::Alive;
+
+        if self.rule.next_state(alive, self.neighbor_count(x, y)) {
+            LifeCell::Alive
+        } else {
+            LifeCell::Dead
+        }
+    }
+
+    /// Work out which cells will be born and which will die in the next generation, without
+    /// stepping.
+    pub fn next_changes(&self) -> Vec<Vec<Change>> {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| match (self.get_cell(x, y), self.next_cell(x, y)) {
+                        (LifeCell::Dead, LifeCell::Alive) => Change::Birth,
+                        (LifeCell::Alive, LifeCell::Dead) => Change::Death,
+                        _ => Change::Same,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     /// Set the status of a cell.
     pub fn set_cell(&mut self, x: usize, y: usize, state: LifeCell) {
         if x < self.width && y < self.height {
             self.cells[self.cur_page][y][x] = state;
+            self.edits += 1;
+
+            // Earlier generations can't say anything about where edited cells are going
+            self.fingerprints.clear();
+
+            if let Some(ages) = self.ages.as_mut() {
+                ages[y][x] = match state {
+                    LifeCell::Alive => 0,
+                    LifeCell::Dead => AGE_UNKNOWN,
+                };
+            }
         }
     }
 
@@ -107,12 +398,19 @@ impl Life {
         for row in &mut self.cells[self.cur_page] {
             row.fill(LifeCell::Dead);
         }
+
+        self.edits += 1;
+        self.generation = 0;
+        self.reset_ages();
+        self.start_history();
     }
 
-    /// Draw a horizontal line
+    /// Draw a horizontal line centered on a cell. Lines longer than the grid go all the way
+    /// around it once.
     pub fn horizontal_line(&mut self, x: usize, y: usize, length: u32) {
-        let startx = (x + self.width - length as usize / 2) % self.width;
-        let endx = startx + length as usize;
+        let length = (length as usize).min(self.width);
+        let startx = (x % self.width + self.width - length / 2) % self.width;
+        let endx = startx + length;
 
         let y = y % self.height;
 
@@ -122,45 +420,241 @@ impl Life {
         }
     }
 
+    /// Draw a vertical line centered on a cell. Lines longer than the grid go all the way around
+    /// it once.
+    pub fn vertical_line(&mut self, x: usize, y: usize, length: u32) {
+        let length = (length as usize).min(self.height);
+        let starty = (y % self.height + self.height - length / 2) % self.height;
+        let endy = starty + length;
+
+        let x = x % self.width;
+
+        for i in starty..endy {
+            let y = i % self.height;
+            self.set_cell(x, y, LifeCell::Alive);
+        }
+    }
+
+    /// Draw a line between two points with Bresenham's algorithm.
+    ///
+    /// Coordinates wrap around the grid, so they can be negative or past the edges.
+    pub fn line(&mut self, x0: isize, y0: isize, x1: isize, y1: isize, state: LifeCell) {
+        for (x, y) in shape::line_cells(x0, y0, x1, y1) {
+            self.set_cell_wrapped(x, y, state);
+        }
+    }
+
+    /// Set the status of a cell, wrapping the coordinates around the grid.
+    pub fn set_cell_wrapped(&mut self, x: isize, y: isize, state: LifeCell) {
+        if self.width > 0 && self.height > 0 {
+            self.set_cell(
+                x.rem_euclid(self.width as isize) as usize,
+                y.rem_euclid(self.height as isize) as usize,
+                state,
+            );
+        }
+    }
+
+    /// Copy a region of the grid into a pattern.
+    ///
+    /// The region wraps around the edges of the grid.
+    pub fn copy_region(&self, x: usize, y: usize, width: usize, height: usize) -> Pattern {
+        let width = width.min(self.width);
+        let height = height.min(self.height);
+        let mut pattern = Pattern::new(width, height);
+
+        for py in 0..height {
+            for px in 0..width {
+                pattern.set_cell(
+                    px,
+                    py,
+                    self.get_cell((x + px) % self.width, (y + py) % self.height),
+                );
+            }
+        }
+
+        pattern
+    }
+
+    /// Set all the cells in a region to the same state.
+    ///
+    /// The region wraps around the edges of the grid.
+    pub fn fill_region(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        state: LifeCell,
+    ) {
+        for py in 0..height.min(self.height) {
+            for px in 0..width.min(self.width) {
+                self.set_cell((x + px) % self.width, (y + py) % self.height, state);
+            }
+        }
+    }
+
+    /// Kill all the cells outside a region.
+    ///
+    /// The region wraps around the edges of the grid.
+    pub fn clear_outside(&mut self, x: usize, y: usize, width: usize, height: usize) {
+        let keep = self.copy_region(x, y, width, height);
+
+        self.clear();
+        self.paste(&keep, x, y, PasteMode::Copy);
+    }
+
+    /// Rotate or reflect a region in place, keeping its top-left corner where it is.
+    ///
+    /// Rotating or transposing a region that isn't square changes its shape, so the cells it
+    /// used to cover are cleared first. Returns the new width and height of the region, or
+    /// `None` without changing anything if the new shape doesn't fit in the universe or would
+    /// cover live cells outside the region.
+    pub fn transform_region(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        transform: Transform,
+    ) -> Option<(usize, usize)> {
+        let pattern = self.copy_region(x, y, width, height).transformed(transform);
+        let (new_w, new_h) = (pattern.width(), pattern.height());
+
+        if new_w > self.width || new_h > self.height {
+            return None;
+        }
+
+        for dy in 0..new_h {
+            for dx in 0..new_w {
+                let inside = dx < width && dy < height;
+                let (cx, cy) = ((x + dx) % self.width, (y + dy) % self.height);
+
+                if !inside && self.get_cell(cx, cy) == LifeCell::Alive {
+                    return None;
+                }
+            }
+        }
+
+        self.fill_region(x, y, width, height, LifeCell::Dead);
+        self.paste(&pattern, x, y, PasteMode::Copy);
+
+        Some((new_w, new_h))
+    }
+
+    /// Find the region a flood fill from a cell would change.
+    ///
+    /// The region is every cell in the same state as the starting cell that can be reached from
+    /// it by steps up, down, left, or right, wrapping around the edges. It's returned as a
+    /// pattern the size of the grid with the region's cells alive, so pasting it with
+    /// [`PasteMode::Xor`] does the fill.
+    pub fn flood_region(&self, x: usize, y: usize) -> Pattern {
+        let mut region = Pattern::new(self.width, self.height);
+
+        if x >= self.width || y >= self.height {
+            return region;
+        }
+
+        let state = self.get_cell(x, y);
+        let mut queue = VecDeque::from([(x, y)]);
+
+        region.set_cell(x, y, LifeCell::Alive);
+
+        while let Some((x, y)) = queue.pop_front() {
+            let neighbors = [
+                ((x + self.width - 1) % self.width, y),
+                ((x + 1) % self.width, y),
+                (x, (y + self.height - 1) % self.height),
+                (x, (y + 1) % self.height),
+            ];
+
+            for (nx, ny) in neighbors {
+                if self.get_cell(nx, ny) == state && region.get_cell(nx, ny) == LifeCell::Dead {
+                    region.set_cell(nx, ny, LifeCell::Alive);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        region
+    }
+
+    /// Paste a pattern with its top-left corner at a given cell.
+    ///
+    /// The pattern wraps around the edges of the grid.
+    pub fn paste(&mut self, pattern: &Pattern, x: usize, y: usize, mode: PasteMode) {
+        for py in 0..pattern.height().min(self.height) {
+            for px in 0..pattern.width().min(self.width) {
+                let gx = (x + px) % self.width;
+                let gy = (y + py) % self.height;
+
+                let state = mode.combine(self.get_cell(gx, gy), pattern.get_cell(px, py));
+
+                if state != self.get_cell(gx, gy) {
+                    self.set_cell(gx, gy, state);
+                }
+            }
+        }
+    }
+
     /// Single step the life simuation.
     pub fn step(&mut self) {
         let other_page = if self.cur_page == 0 { 1 } else { 0 };
+        let (mut population, mut births, mut deaths) = (0, 0, 0);
+
+        if self.fingerprints.is_empty() {
+            self.fingerprints.push_back(self.fingerprint());
+        }
 
         for y in 0..self.height {
             for x in 0..self.width {
-                let n = if y > 0 { y - 1 } else { self.height - 1 };
-                let s = if y < self.height - 1 { y + 1 } else { 0 };
-                let w = if x > 0 { x - 1 } else { self.width - 1 };
-                let e = if x < self.width - 1 { x + 1 } else { 0 };
-
-                let count = self.get_cell_weight(w, n)
-                    + self.get_cell_weight(x, n)
-                    + self.get_cell_weight(e, n)
-                    + self.get_cell_weight(w, y)
-                    + self.get_cell_weight(e, y)
-                    + self.get_cell_weight(w, s)
-                    + self.get_cell_weight(x, s)
-                    + self.get_cell_weight(e, s);
-
                 let alive = self.get_cell(x, y) == LifeCell::Alive;
+                let next_alive = self.rule.next_state(alive, self.neighbor_count(x, y));
+
+                population += next_alive as usize;
+                births += (next_alive && !alive) as usize;
+                deaths += (alive && !next_alive) as usize;
 
-                if alive {
-                    self.cells[other_page][y][x] = if count == 2 || count == 3 {
-                        LifeCell::Alive
-                    } else {
-                        LifeCell::Dead
-                    };
+                self.cells[other_page][y][x] = if next_alive {
+                    LifeCell::Alive
                 } else {
-                    self.cells[other_page][y][x] = if count == 3 {
-                        LifeCell::Alive
-                    } else {
-                        LifeCell::Dead
-                    }
-                }
+                    LifeCell::Dead
+                };
             } // for x
         } // for y
 
+        if let Some(ages) = self.ages.as_mut() {
+            for (y, row) in ages.iter_mut().enumerate() {
+                for (x, age) in row.iter_mut().enumerate() {
+                    // Unknown ages stay unknown, and known ages stop just short of it
+                    if self.cells[other_page][y][x] != self.cells[self.cur_page][y][x] {
+                        *age = 0;
+                    } else if *age < AGE_UNKNOWN - 1 {
+                        *age += 1;
+                    }
+                }
+            }
+        }
+
         self.cur_page = other_page;
+        self.generation += 1;
+
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(Stats {
+            generation: self.generation,
+            population,
+            births,
+            deaths,
+        });
+
+        if self.fingerprints.len() == FINGERPRINT_LENGTH {
+            self.fingerprints.pop_front();
+        }
+
+        self.fingerprints.push_back(self.fingerprint());
     }
 
     /// Resize the life grid.
@@ -175,14 +669,147 @@ impl Life {
             }
         }
 
+        if let Some(ages) = self.ages.as_mut() {
+            ages.resize(height, vec![AGE_UNKNOWN; width]);
+
+            for row in ages.iter_mut() {
+                row.resize(width, AGE_UNKNOWN);
+            }
+        }
+
         self.width = width;
         self.height = height;
+        self.watched = None;
+        self.fingerprints.clear();
+    }
+
+    /// Resize the grid, moving the live cells so they're centered in it.
+    ///
+    /// The grid is never made smaller than the bounding box of the live cells, so none are lost.
+    /// Returns a function that tells where a cell moved to, for moving things like a cursor along
+    /// with the cells.
+    pub fn resize_centered(
+        &mut self,
+        width: usize,
+        height: usize,
+    ) -> impl Fn(usize, usize) -> (usize, usize) + use<> {
+        let (old_w, old_h) = (self.width.max(1), self.height.max(1));
+        let (bx, by, bw, bh) = self.bounding_box().unwrap_or((0, 0, 0, 0));
+
+        let width = width.max(bw);
+        let height = height.max(bh);
+        let left = (width - bw) / 2;
+        let top = (height - bh) / 2;
+
+        let old_cells = std::mem::take(&mut self.cells[self.cur_page]);
+        let old_ages = self.ages.take();
+        let generation = self.generation;
+        let history = std::mem::take(&mut self.history);
+
+        self.ages = old_ages.as_ref().map(|_| Vec::new());
+        self.init(width, height);
+        self.generation = generation;
+        self.history = history;
+
+        for y in 0..bh {
+            for x in 0..bw {
+                let (ox, oy) = ((bx + x) % old_w, (by + y) % old_h);
+
+                self.cells[self.cur_page][top + y][left + x] = old_cells[oy][ox];
+
+                if let (Some(ages), Some(old_ages)) = (self.ages.as_mut(), old_ages.as_ref()) {
+                    ages[top + y][left + x] = old_ages[oy][ox];
+                }
+            }
+        }
+
+        move |x, y| {
+            (
+                ((x + old_w - bx % old_w) % old_w + left) % width.max(1),
+                ((y + old_h - by % old_h) % old_h + top) % height.max(1),
+            )
+        }
     }
 
     /// Get a reference to all the cells.
     pub fn get_cells(&self) -> &Vec<Vec<LifeCell>> {
         &self.cells[self.cur_page]
     }
+
+    /// Get the width of the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the bounding box of all live cells as `(x, y, width, height)`.
+    ///
+    /// Across edges that are joined, the box is placed so it doesn't include the largest empty
+    /// gap, and `x + width` might be past the right edge of the grid. Top and bottom edges joined
+    /// mirrored, like on a Klein bottle, are treated as not joined, since a box across them isn't
+    /// a rectangle. Returns `None` if there are no live cells.
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut cols = vec![false; self.width];
+        let mut rows = vec![false; self.height];
+
+        for (y, row) in self.cells[self.cur_page].iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if *cell == LifeCell::Alive {
+                    cols[x] = true;
+                    rows[y] = true;
+                }
+            }
+        }
+
+        let (wraps_x, wraps_y) = match self.topology {
+            Topology::Torus => (true, true),
+            Topology::Plane => (false, false),
+            Topology::Cylinder | Topology::Klein => (true, false),
+        };
+
+        let (x, width) = occupied_span(&cols, wraps_x)?;
+        let (y, height) = occupied_span(&rows, wraps_y)?;
+
+        Some((x, y, width, height))
+    }
+}
+
+/// Find the shortest span that covers all the occupied entries, wrapping around the end if
+/// `wraps` is true.
+///
+/// Returns `(start, length)`, or `None` if nothing is occupied.
+fn occupied_span(occupied: &[bool], wraps: bool) -> Option<(usize, usize)> {
+    let len = occupied.len();
+    let first = occupied.iter().position(|o| *o)?;
+
+    if !wraps {
+        let last = occupied.iter().rposition(|o| *o)?;
+        return Some((first, last + 1 - first));
+    }
+
+    // Find the longest run of empty entries, wrapping around the end
+    let mut best_start = 0;
+    let mut best_len = 0;
+    let mut run_len = 0;
+
+    for i in 0..len * 2 {
+        if occupied[i % len] {
+            run_len = 0;
+        } else {
+            run_len += 1;
+
+            if run_len > best_len {
+                best_len = run_len;
+                best_start = (i + 1 - run_len) % len;
+            }
+        }
+    }
+
+    Some(((best_start + best_len) % len, len - best_len))
 }
 
 impl Default for Life {
@@ -191,3 +818,42 @@ impl Default for Life {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_is_centered_on_the_cell() {
+        let mut life = Life::new();
+        life.init(10, 10);
+        life.horizontal_line(5, 2, 3);
+
+        assert_eq!(life.population(), 3);
+        assert!((4..=6).all(|x| life.get_cell(x, 2) == LifeCell::Alive));
+    }
+
+    #[test]
+    fn line_wraps_around_the_edge() {
+        let mut life = Life::new();
+        life.init(10, 10);
+        life.vertical_line(3, 0, 3);
+
+        assert_eq!(life.population(), 3);
+        assert!(
+            [9, 0, 1]
+                .iter()
+                .all(|y| life.get_cell(3, *y) == LifeCell::Alive)
+        );
+    }
+
+    #[test]
+    fn line_longer_than_the_grid_fills_one_row_or_column() {
+        let mut life = Life::new();
+        life.init(10, 8);
+        life.horizontal_line(0, 0, 999);
+        life.vertical_line(9, 7, u32::MAX);
+
+        assert_eq!(life.population(), 10 + 8 - 1);
+    }
+}
@@ -1,17 +1,40 @@
 //! Implementation of [Conway's Game of
 //! Life](https://en.wikipedia.org/wiki/Conway%27s_Game_of_Life) using [Ratatui](ratatui.rs).
-use crossterm::event;
+use crossterm::{
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    },
+    execute,
+};
 use ratatui::{self, DefaultTerminal};
 use std::time::{Duration, Instant};
 
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+mod census;
+mod command;
+mod config;
+mod keymap;
+mod library;
 mod life;
+mod pattern;
+mod period;
+mod rule;
+mod search;
+mod session;
+mod shape;
+mod soup;
+mod theme;
+mod tracker;
 mod util;
+mod viewport;
 mod widgets;
 mod windows;
 
-use windows::{RootWindow, WindowDrawResult};
+use config::Config;
+use search::Search;
+use session::Session;
+use windows::{MIN_SIZE, RootWindow, WindowDrawResult};
 
 /// Application-level event types.
 #[derive(PartialEq)]
@@ -65,19 +88,23 @@ struct App {
 
 impl App {
     /// Create a new App object.
-    fn new() -> Self {
+    fn new(config: Config, session: Option<Session>) -> Self {
         Self {
             tick_rate: Duration::from_millis(20),
             next_tick: None,
-            root_window: RootWindow::new(),
+            root_window: RootWindow::new(config, session),
         }
     }
 
     fn init(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
         let size = terminal.size()?;
 
-        if size.width < 40 || size.height < 20 {
-            return Err("terminal too small".into());
+        if size.width < MIN_SIZE.width || size.height < MIN_SIZE.height {
+            return Err(format!(
+                "terminal too small, it needs to be at least {}x{}",
+                MIN_SIZE.width, MIN_SIZE.height
+            )
+            .into());
         }
 
         self.root_window.init(size);
@@ -145,9 +172,104 @@ impl App {
     }
 }
 
+/// Command line usage.
+const USAGE: &str = "\
+Usage: rustlife [--fresh]
+       rustlife search [search options]
+
+Options:
+  -f, --fresh  Start with a new random soup instead of the saved session
+  -h, --help   Show this help
+
+Search options, with soup settings and rule defaulting to the config file:
+  --soups N          Number of soups to run (default 100)
+  --seed N           Seed of the first soup, counting up from there (default random)
+  --size WxH         Size of each soup (default 16x16)
+  --density D        Chance of each cell being alive, 0.0 to 1.0
+  --symmetry S       C1, C2, C4, D2, D4, or D8
+  --rule RULE        Rule in B/S or S/B notation
+  --generations N    Most generations to run each soup (default 10000)
+  --output FILE      Results file (default rustlife-search-<time>.txt)";
+
+/// Mouse capture and bracketed paste, turned off again when dropped.
+///
+/// Dropping is what turns them off, so the terminal is put back however the app stops, including
+/// an early return or a panic.
+struct TerminalModes;
+
+impl TerminalModes {
+    /// Turn on mouse capture and bracketed paste.
+    fn enable() -> Result<Self> {
+        execute!(std::io::stdout(), EnableMouseCapture, EnableBracketedPaste)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalModes {
+    fn drop(&mut self) {
+        let _ = execute!(
+            std::io::stdout(),
+            DisableMouseCapture,
+            DisableBracketedPaste
+        );
+    }
+}
+
+/// Print an error and exit.
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("rustlife: {message}");
+    std::process::exit(1);
+}
+
 /// Main.
 fn main() -> Result<()> {
-    let mut app = App::new();
-    ratatui::run(|terminal| app.run(terminal))?;
-    Ok(())
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    // Searching runs without the terminal UI
+    if args.first().is_some_and(|arg| arg == "search") {
+        let config = Config::load().unwrap_or_else(|err| fail(err));
+        let search = Search::parse(args.into_iter().skip(1), &config)
+            .unwrap_or_else(|err| fail(format!("{err}\n\n{USAGE}")));
+        search.run().unwrap_or_else(|err| fail(err));
+        return Ok(());
+    }
+
+    let mut fresh = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "-f" | "--fresh" => fresh = true,
+            _ => fail(format!("unknown argument {arg}\n\n{USAGE}")),
+        }
+    }
+
+    // Load the config and session before taking over the terminal so errors can be seen
+    let config = Config::load().unwrap_or_else(|err| fail(err));
+
+    let session = if config.save_session && !fresh {
+        Session::load().unwrap_or_else(|err| fail(format!("{err}\nUse --fresh to start over")))
+    } else {
+        None
+    };
+
+    let mut app = App::new(config, session);
+
+    let result = ratatui::run(|terminal| {
+        let _modes = TerminalModes::enable()?;
+        app.run(terminal)
+    });
+
+    if result.is_ok()
+        && let Some(session) = app.root_window.session()
+        && let Err(err) = session.save()
+    {
+        fail(format!("can't save the session: {err}"));
+    }
+
+    result
 }
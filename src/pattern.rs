@@ -0,0 +1,525 @@
+//! Patterns
+//!
+//! A pattern is a rectangle of cells that isn't attached to a Life grid, like the clipboard.
+//!
+//! Patterns can be read from and written to [RLE](https://conwaylife.com/wiki/Run_Length_Encoded)
+//! text, and read from [plaintext](https://conwaylife.com/wiki/Plaintext) (`.cells`) files, the
+//! formats used by Golly and LifeViewer.
+use crate::{life::LifeCell, rule::Rule};
+
+/// Longest line to write in RLE output, as recommended by the format.
+const RLE_LINE_LENGTH: usize = 70;
+
+/// Widest or tallest pattern that can be read, so a bad file or paste can't use up all the memory.
+pub const MAX_SIZE: usize = 4096;
+
+/// How a pattern is combined with the cells already in the grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PasteMode {
+    /// Live cells are added, nothing is removed.
+    Or,
+
+    /// Live cells toggle the cells underneath.
+    Xor,
+
+    /// Only cells alive in both survive.
+    And,
+
+    /// The pattern replaces everything underneath it.
+    Copy,
+}
+
+impl PasteMode {
+    /// Get the next paste mode in the cycle.
+    pub fn next(self) -> Self {
+        match self {
+            PasteMode::Or => PasteMode::Xor,
+            PasteMode::Xor => PasteMode::And,
+            PasteMode::And => PasteMode::Copy,
+            PasteMode::Copy => PasteMode::Or,
+        }
+    }
+
+    /// Get the name of the paste mode for the status bar.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PasteMode::Or => "OR",
+            PasteMode::Xor => "XOR",
+            PasteMode::And => "AND",
+            PasteMode::Copy => "COPY",
+        }
+    }
+
+    /// Combine a grid cell with a pattern cell.
+    pub fn combine(&self, grid: LifeCell, pattern: LifeCell) -> LifeCell {
+        let alive = match self {
+            PasteMode::Or => grid == LifeCell::Alive || pattern == LifeCell::Alive,
+            PasteMode::Xor => (grid == LifeCell::Alive) != (pattern == LifeCell::Alive),
+            PasteMode::And => grid == LifeCell::Alive && pattern == LifeCell::Alive,
+            PasteMode::Copy => pattern == LifeCell::Alive,
+        };
+
+        if alive {
+            LifeCell::Alive
+        } else {
+            LifeCell::Dead
+        }
+    }
+}
+
+/// A way to rotate or reflect a pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    /// Rotate 90° clockwise.
+    RotateCw,
+
+    /// Rotate 90° counterclockwise.
+    RotateCcw,
+
+    /// Rotate 180°.
+    Rotate180,
+
+    /// Mirror left to right.
+    FlipHorizontal,
+
+    /// Mirror top to bottom.
+    FlipVertical,
+
+    /// Swap rows and columns, mirroring across the top-left to bottom-right diagonal.
+    Transpose,
+}
+
+impl Transform {
+    /// Check if the transform swaps the width and height.
+    pub fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Transform::RotateCw | Transform::RotateCcw | Transform::Transpose
+        )
+    }
+}
+
+/// A rectangle of cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<LifeCell>>,
+}
+
+impl Pattern {
+    /// Create a new empty pattern.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![vec![LifeCell::Dead; width]; height],
+        }
+    }
+
+    /// Get the width of the pattern.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the pattern.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells
+            .iter()
+            .flatten()
+            .filter(|state| **state == LifeCell::Alive)
+            .count()
+    }
+
+    /// Get the bounding box of the live cells as `(x, y, width, height)`, or `None` if there
+    /// aren't any.
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        let alive = |row: &Vec<LifeCell>| row.contains(&LifeCell::Alive);
+
+        let top = self.cells.iter().position(alive)?;
+        let bottom = self.cells.iter().rposition(alive)?;
+        let rows = &self.cells[top..=bottom];
+
+        let left = rows
+            .iter()
+            .filter_map(|row| row.iter().position(|state| *state == LifeCell::Alive))
+            .min()?;
+        let right = rows
+            .iter()
+            .filter_map(|row| row.iter().rposition(|state| *state == LifeCell::Alive))
+            .max()?;
+
+        Some((left, top, right - left + 1, bottom - top + 1))
+    }
+
+    /// Get the status of a cell. Cells outside the pattern are dead.
+    pub fn get_cell(&self, x: usize, y: usize) -> LifeCell {
+        if x < self.width && y < self.height {
+            self.cells[y][x]
+        } else {
+            LifeCell::Dead
+        }
+    }
+
+    /// Set the status of a cell.
+    pub fn set_cell(&mut self, x: usize, y: usize, state: LifeCell) {
+        if x < self.width && y < self.height {
+            self.cells[y][x] = state;
+        }
+    }
+
+    /// Parse a pattern from RLE or plaintext, whichever it looks like.
+    pub fn from_text(text: &str) -> crate::Result<Pattern> {
+        let is_rle = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .is_some_and(|line| line.starts_with('x') && line.contains('='));
+
+        if is_rle {
+            Pattern::from_rle(text)
+        } else {
+            Pattern::from_plaintext(text)
+        }
+    }
+
+    /// Get the rule from the header of RLE text, if it has one.
+    pub fn rle_rule(text: &str) -> Option<crate::Result<Rule>> {
+        let header = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+
+        strip_topology(header)
+            .split(',')
+            .filter_map(|field| field.split_once('='))
+            .find(|(name, _)| name.trim() == "rule")
+            .map(|(_, value)| Rule::parse(value))
+    }
+
+    /// Parse a pattern from RLE text.
+    ///
+    /// The rule in the header is ignored, see [`Pattern::rle_rule`]. States other than dead (`b` or
+    /// `.`) count as alive.
+    pub fn from_rle(text: &str) -> crate::Result<Pattern> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        let header = lines.next().ok_or("missing RLE header")?;
+        let mut width = 0;
+        let mut height = 0;
+
+        for field in strip_topology(header).split(',') {
+            let Some((name, value)) = field.split_once('=') else {
+                return Err(format!("bad RLE header: {header}").into());
+            };
+
+            match name.trim() {
+                "x" => width = value.trim().parse()?,
+                "y" => height = value.trim().parse()?,
+                _ => (),
+            }
+        }
+
+        check_size(width, height)?;
+
+        let mut rows: Vec<Vec<LifeCell>> = vec![Vec::new()];
+        let mut count: usize = 0;
+
+        'lines: for line in lines {
+            for c in line.chars() {
+                let run = count.max(1);
+
+                match c {
+                    '0'..='9' => {
+                        count = count
+                            .checked_mul(10)
+                            .and_then(|count| count.checked_add(c.to_digit(10).unwrap() as usize))
+                            .filter(|count| *count <= MAX_SIZE)
+                            .ok_or("run too long in RLE")?;
+                        continue;
+                    }
+                    'b' | '.' => rows
+                        .last_mut()
+                        .unwrap()
+                        .extend((0..run).map(|_| LifeCell::Dead)),
+                    'o' | 'A'..='X' => rows
+                        .last_mut()
+                        .unwrap()
+                        .extend((0..run).map(|_| LifeCell::Alive)),
+                    '$' => rows.extend((0..run).map(|_| Vec::new())),
+                    '!' => break 'lines,
+                    c if c.is_whitespace() => (),
+                    c => return Err(format!("unexpected character in RLE: {c}").into()),
+                }
+
+                check_size(rows.last().map_or(0, Vec::len), rows.len())?;
+                count = 0;
+            }
+        }
+
+        Ok(Pattern::from_rows(rows, width, height))
+    }
+
+    /// Parse a pattern from plaintext, where `.` is dead and `O` or `*` is alive.
+    ///
+    /// Lines starting with `!` are comments.
+    pub fn from_plaintext(text: &str) -> crate::Result<Pattern> {
+        let mut rows = Vec::new();
+
+        for line in text.lines().filter(|line| !line.starts_with('!')) {
+            let row = line
+                .trim_end()
+                .chars()
+                .map(|c| match c {
+                    '.' => Ok(LifeCell::Dead),
+                    'O' | '*' => Ok(LifeCell::Alive),
+                    c => Err(format!("unexpected character in plaintext pattern: {c}")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            rows.push(row);
+        }
+
+        // Blank lines at the ends are usually just part of the paste
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+
+        if rows.is_empty() {
+            return Err("empty pattern".into());
+        }
+
+        check_size(rows.iter().map(Vec::len).max().unwrap_or(0), rows.len())?;
+
+        Ok(Pattern::from_rows(rows, 0, 0))
+    }
+
+    /// Make a pattern from rows of cells, which can be ragged.
+    ///
+    /// The pattern is at least `width` by `height`.
+    fn from_rows(rows: Vec<Vec<LifeCell>>, width: usize, height: usize) -> Pattern {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0).max(width);
+        let height = rows.len().max(height);
+        let mut pattern = Pattern::new(width, height);
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, state) in row.iter().enumerate() {
+                pattern.set_cell(x, y, *state);
+            }
+        }
+
+        pattern
+    }
+
+    /// Write the pattern as RLE text, with `#C` comment lines at the top.
+    pub fn to_rle(&self, rule: &Rule, comments: &[String]) -> String {
+        let mut runs: Vec<(usize, char)> = Vec::new();
+
+        for (y, row) in self.cells.iter().enumerate() {
+            if y > 0 {
+                push_run(&mut runs, '$');
+            }
+
+            let len = row
+                .iter()
+                .rposition(|state| *state == LifeCell::Alive)
+                .map_or(0, |x| x + 1);
+
+            for state in &row[..len] {
+                push_run(&mut runs, if *state == LifeCell::Alive { 'o' } else { 'b' });
+            }
+        }
+
+        // Empty rows at the bottom don't need to be written
+        if runs.last().is_some_and(|(_, c)| *c == '$') {
+            runs.pop();
+        }
+
+        runs.push((1, '!'));
+
+        let mut rle: String = comments
+            .iter()
+            .map(|comment| format!("#C {comment}\n"))
+            .collect();
+
+        rle.push_str(&format!(
+            "x = {}, y = {}, rule = {rule}\n",
+            self.width, self.height
+        ));
+        let mut line = String::new();
+
+        for (count, c) in runs {
+            let token = if count > 1 {
+                format!("{count}{c}")
+            } else {
+                c.to_string()
+            };
+
+            if line.len() + token.len() > RLE_LINE_LENGTH {
+                rle.push_str(&line);
+                rle.push('\n');
+                line.clear();
+            }
+
+            line.push_str(&token);
+        }
+
+        rle.push_str(&line);
+        rle.push('\n');
+
+        rle
+    }
+
+    /// Make a rotated or reflected copy of the pattern.
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let (width, height) = if transform.swaps_axes() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+
+        let mut pattern = Pattern::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                // Find the cell in the original that lands here
+                let (sx, sy) = match transform {
+                    Transform::RotateCw => (y, self.height - 1 - x),
+                    Transform::RotateCcw => (self.width - 1 - y, x),
+                    Transform::Rotate180 => (self.width - 1 - x, self.height - 1 - y),
+                    Transform::FlipHorizontal => (self.width - 1 - x, y),
+                    Transform::FlipVertical => (x, self.height - 1 - y),
+                    Transform::Transpose => (y, x),
+                };
+
+                pattern.set_cell(x, y, self.get_cell(sx, sy));
+            }
+        }
+
+        pattern
+    }
+}
+
+/// Check that a pattern being read isn't bigger than [`MAX_SIZE`].
+fn check_size(width: usize, height: usize) -> crate::Result<()> {
+    if width > MAX_SIZE || height > MAX_SIZE {
+        Err(format!("pattern is bigger than {MAX_SIZE}x{MAX_SIZE}").into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Remove the topology Golly adds after the rule in an RLE header, like `:T100,100`.
+fn strip_topology(header: &str) -> &str {
+    header.split(':').next().unwrap_or_default()
+}
+
+/// Add one character to a list of RLE runs, extending the last run if it's the same.
+fn push_run(runs: &mut Vec<(usize, char)>, c: char) {
+    match runs.last_mut() {
+        Some((count, last)) if *last == c => *count += 1,
+        _ => runs.push((1, c)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER_RLE: &str = "#C A glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+
+    #[test]
+    fn reads_rle() {
+        let pattern = Pattern::from_rle(GLIDER_RLE).unwrap();
+
+        assert_eq!(pattern, Pattern::from_plaintext(".O.\n..O\nOOO").unwrap());
+        assert_eq!(
+            Pattern::rle_rule(GLIDER_RLE).unwrap().unwrap(),
+            Rule::default()
+        );
+    }
+
+    #[test]
+    fn rle_header_sets_the_minimum_size() {
+        let pattern = Pattern::from_rle("x = 5, y = 4\no!").unwrap();
+
+        assert_eq!((pattern.width(), pattern.height()), (5, 4));
+        assert_eq!(pattern.population(), 1);
+    }
+
+    #[test]
+    fn rle_ignores_golly_topology() {
+        let text = "x = 3, y = 1, rule = B3/S23:T10,10\n3o!";
+
+        assert_eq!(Pattern::rle_rule(text).unwrap().unwrap(), Rule::default());
+        assert_eq!(Pattern::from_rle(text).unwrap().population(), 3);
+    }
+
+    #[test]
+    fn writes_rle_that_reads_back() {
+        let pattern = Pattern::from_plaintext("OO.O\n....\n.O..\n.OOO").unwrap();
+        let rle = pattern.to_rle(&Rule::default(), &["Test".to_string()]);
+
+        assert!(rle.starts_with("#C Test\nx = 4, y = 4, rule = B3/S23\n"));
+        assert_eq!(Pattern::from_text(&rle).unwrap(), pattern);
+    }
+
+    #[test]
+    fn writes_runs_and_wraps_long_lines() {
+        let mut pattern = Pattern::new(200, 2);
+        for x in (0..200).step_by(2) {
+            pattern.set_cell(x, 0, LifeCell::Alive);
+        }
+        pattern.set_cell(0, 1, LifeCell::Alive);
+        pattern.set_cell(1, 1, LifeCell::Alive);
+
+        let rle = pattern.to_rle(&Rule::default(), &[]);
+
+        assert!(rle.lines().all(|line| line.len() <= RLE_LINE_LENGTH));
+        assert!(rle.contains("$2o!"));
+        assert_eq!(Pattern::from_rle(&rle).unwrap(), pattern);
+    }
+
+    #[test]
+    fn reads_plaintext() {
+        let pattern = Pattern::from_text("!Name: Blinker\n\n*O.\n\n").unwrap();
+
+        assert_eq!((pattern.width(), pattern.height()), (3, 2));
+        assert_eq!(pattern.get_cell(0, 1), LifeCell::Alive);
+        assert_eq!(pattern.get_cell(1, 1), LifeCell::Alive);
+        assert_eq!(pattern.get_cell(2, 1), LifeCell::Dead);
+    }
+
+    #[test]
+    fn rejects_bad_text() {
+        assert!(Pattern::from_text("").is_err());
+        assert!(Pattern::from_text("hello").is_err());
+        assert!(Pattern::from_rle("x = 3, y\n3o!").is_err());
+        assert!(Pattern::from_rle("x = 3, y = 1\n3q!").is_err());
+        assert!(Pattern::from_rle("x = -3, y = 1\n3o!").is_err());
+    }
+
+    #[test]
+    fn rejects_huge_run_counts() {
+        assert!(Pattern::from_rle("x = 1, y = 1\n99999999999999999999999o!").is_err());
+        assert!(Pattern::from_rle("x = 1, y = 1\n99999999999999999999999$o!").is_err());
+        assert!(Pattern::from_rle("x = 1, y = 1\n5000o!").is_err());
+    }
+
+    #[test]
+    fn rejects_huge_patterns() {
+        assert!(Pattern::from_rle("x = 100000, y = 100000\no!").is_err());
+        assert!(Pattern::from_rle("x = 1, y = 1\n4000o4000o!").is_err());
+        assert!(Pattern::from_rle("x = 1, y = 1\n4000$4000$o!").is_err());
+        assert!(Pattern::from_rle(&format!("x = 1, y = 1\n{MAX_SIZE}o!")).is_ok());
+        assert!(Pattern::from_plaintext(&"O".repeat(MAX_SIZE + 1)).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Period detection
+//!
+//! Every generation, the live cells are hashed relative to their bounding box, so the hash doesn't
+//! change when a pattern moves. When the latest hash turns up earlier in the history, the pattern
+//! is periodic, and how far its bounding box moved in between tells a spaceship from an
+//! oscillator.
+use crate::{life::LifeCell, pattern::Pattern};
+use std::{
+    collections::VecDeque,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Fingerprint of the live cells in one generation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fingerprint {
+    /// Generation the fingerprint is for.
+    pub generation: u64,
+
+    /// Hash of the live cells relative to their bounding box, or `None` if there aren't any.
+    pub hash: Option<u64>,
+
+    /// Top-left corner of the bounding box.
+    pub origin: (usize, usize),
+}
+
+impl Fingerprint {
+    /// Make the fingerprint of the cells in a pattern whose top-left corner is at `origin`.
+    pub fn new(generation: u64, pattern: &Pattern, origin: (usize, usize)) -> Self {
+        let Some((x, y, width, height)) = pattern.bounding_box() else {
+            return Self {
+                generation,
+                hash: None,
+                origin,
+            };
+        };
+
+        let mut hasher = DefaultHasher::new();
+        (width, height).hash(&mut hasher);
+
+        for py in y..y + height {
+            for px in x..x + width {
+                (pattern.get_cell(px, py) == LifeCell::Alive).hash(&mut hasher);
+            }
+        }
+
+        Self {
+            generation,
+            hash: Some(hasher.finish()),
+            origin: (origin.0 + x, origin.1 + y),
+        }
+    }
+}
+
+/// What a pattern that repeats itself does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Behavior {
+    /// Every cell died.
+    Empty,
+
+    /// Nothing changes from one generation to the next.
+    StillLife,
+
+    /// The pattern repeats in place.
+    Oscillator,
+
+    /// The pattern repeats, moved by `dx` and `dy` cells.
+    Spaceship { dx: isize, dy: isize },
+}
+
+/// How a pattern repeats, once it does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Period {
+    /// What the pattern does.
+    pub behavior: Behavior,
+
+    /// Generations before the pattern repeats.
+    pub period: u64,
+
+    /// First generation the pattern was repeating from.
+    pub since: u64,
+}
+
+impl Period {
+    /// Look for a repeat in a history of fingerprints, oldest first.
+    ///
+    /// The grid size is used to undo wrapping when working out how far a spaceship moved, and
+    /// can be `None` if the cells don't wrap.
+    pub fn detect(history: &VecDeque<Fingerprint>, wrap: Option<(usize, usize)>) -> Option<Period> {
+        let latest = history.back()?;
+
+        // The closest earlier match gives the smallest period
+        let matched = history
+            .iter()
+            .rev()
+            .skip(1)
+            .find(|earlier| earlier.hash == latest.hash)?;
+
+        let period = latest.generation - matched.generation;
+        let shift = |a: &Fingerprint, b: &Fingerprint| {
+            (
+                offset(a.origin.0, b.origin.0, wrap.map(|(w, _)| w)),
+                offset(a.origin.1, b.origin.1, wrap.map(|(_, h)| h)),
+            )
+        };
+        let (dx, dy) = shift(matched, latest);
+
+        // Go back as long as each generation matches the one a period after it the same way
+        let lag = period as usize;
+        let newest = history.len() - 1;
+        let mut first = newest - lag;
+
+        while first > 0 {
+            let (earlier, later) = (&history[first - 1], &history[first - 1 + lag]);
+
+            if earlier.hash != later.hash || shift(earlier, later) != (dx, dy) {
+                break;
+            }
+
+            first -= 1;
+        }
+
+        let behavior = if latest.hash.is_none() {
+            Behavior::Empty
+        } else if (dx, dy) != (0, 0) {
+            Behavior::Spaceship { dx, dy }
+        } else if period == 1 {
+            Behavior::StillLife
+        } else {
+            Behavior::Oscillator
+        };
+
+        Some(Period {
+            behavior,
+            period: if behavior == Behavior::Empty {
+                1
+            } else {
+                period
+            },
+            since: history[first].generation,
+        })
+    }
+
+    /// Describe the speed of a spaceship, like `c/4 diagonal`.
+    pub fn speed(&self, dx: isize, dy: isize) -> String {
+        let distance = dx.unsigned_abs().max(dy.unsigned_abs()) as u64;
+        let divisor = gcd(distance, self.period);
+        let (distance, period) = (distance / divisor, self.period / divisor);
+
+        let speed = match (distance, period) {
+            (1, 1) => "c".to_string(),
+            (1, period) => format!("c/{period}"),
+            (distance, 1) => format!("{distance}c"),
+            (distance, period) => format!("{distance}c/{period}"),
+        };
+
+        if dx == 0 || dy == 0 {
+            format!("{speed} orthogonal")
+        } else if dx.abs() == dy.abs() {
+            format!("{speed} diagonal")
+        } else {
+            format!("({}, {})c/{} oblique", dx.abs(), dy.abs(), self.period)
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    /// Describe the pattern, like `Oscillator, period 3, since generation 120`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.behavior {
+            Behavior::Empty => write!(f, "Died out at generation {}", self.since),
+            Behavior::StillLife => write!(f, "Still life since generation {}", self.since),
+            Behavior::Oscillator => write!(
+                f,
+                "Oscillator, period {}, since generation {}",
+                self.period, self.since
+            ),
+            Behavior::Spaceship { dx, dy } => write!(
+                f,
+                "Spaceship, period {}, {}, since generation {}",
+                self.period,
+                self.speed(dx, dy),
+                self.since
+            ),
+        }
+    }
+}
+
+/// Work out how far a coordinate moved, taking the shorter way around if it wraps.
+fn offset(from: usize, to: usize, wrap: Option<usize>) -> isize {
+    let delta = to as isize - from as isize;
+
+    match wrap {
+        Some(size) if size > 0 => {
+            let size = size as isize;
+            let delta = delta.rem_euclid(size);
+
+            if delta > size / 2 {
+                delta - size
+            } else {
+                delta
+            }
+        }
+        _ => delta,
+    }
+}
+
+/// Find the greatest common divisor.
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a.max(1) } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{life::Life, pattern::PasteMode};
+
+    /// Run a plaintext pattern on a torus for some generations and look for its period.
+    fn run(text: &str, generations: usize) -> Option<Period> {
+        let mut life = Life::new();
+        life.init(20, 20);
+        life.paste(
+            &Pattern::from_plaintext(text).unwrap(),
+            8,
+            8,
+            PasteMode::Copy,
+        );
+
+        for _ in 0..generations {
+            life.step();
+        }
+
+        life.period()
+    }
+
+    #[test]
+    fn block_is_a_still_life() {
+        let period = run("OO\nOO", 2).unwrap();
+
+        assert_eq!(period.behavior, Behavior::StillLife);
+        assert_eq!(period.period, 1);
+        assert_eq!(period.since, 0);
+    }
+
+    #[test]
+    fn blinker_has_period_2() {
+        assert_eq!(run("OOO", 1), None);
+
+        let period = run("OOO", 4).unwrap();
+
+        assert_eq!(period.behavior, Behavior::Oscillator);
+        assert_eq!(period.period, 2);
+        assert_eq!(period.since, 0);
+    }
+
+    #[test]
+    fn glider_is_a_c4_diagonal_spaceship() {
+        let period = run(".O.\n..O\nOOO", 8).unwrap();
+
+        assert_eq!(period.behavior, Behavior::Spaceship { dx: 1, dy: 1 });
+        assert_eq!(period.period, 4);
+        assert_eq!(period.speed(1, 1), "c/4 diagonal");
+    }
+
+    #[test]
+    fn glider_moving_across_the_wrapped_edge_is_still_found() {
+        // Far enough that it wraps around the torus in between
+        let period = run(".O.\n..O\nOOO", 60).unwrap();
+
+        assert_eq!(period.behavior, Behavior::Spaceship { dx: 1, dy: 1 });
+        assert_eq!(period.since, 0);
+    }
+
+    #[test]
+    fn speeds_are_reduced_and_named() {
+        let period = |period| Period {
+            behavior: Behavior::Oscillator,
+            period,
+            since: 0,
+        };
+
+        assert_eq!(period(1).speed(-1, 0), "c orthogonal");
+        assert_eq!(period(4).speed(0, -2), "c/2 orthogonal");
+        assert_eq!(period(3).speed(3, 0), "c orthogonal");
+        assert_eq!(period(2).speed(4, 0), "2c orthogonal");
+        assert_eq!(period(7).speed(2, 0), "2c/7 orthogonal");
+        assert_eq!(period(5).speed(2, -1), "(2, 1)c/5 oblique");
+    }
+
+    #[test]
+    fn offsets_take_the_short_way_around() {
+        assert_eq!(offset(2, 5, None), 3);
+        assert_eq!(offset(5, 2, None), -3);
+        assert_eq!(offset(1, 19, Some(20)), -2);
+        assert_eq!(offset(19, 1, Some(20)), 2);
+    }
+}
@@ -0,0 +1,259 @@
+//! Rules and topologies
+//!
+//! A rule says how many live neighbors make a dead cell come alive and keep a live cell alive. It
+//! is written in [B/S notation](https://conwaylife.com/wiki/Rulestring), like `B3/S23` for
+//! Conway's Life. The older S/B notation, like `23/3`, is read too.
+//!
+//! A topology says how the edges of the grid are joined up.
+use std::fmt;
+
+/// Well-known rules: Life, HighLife, Seeds, Day & Night, Replicator, and Morley.
+pub const KNOWN_RULES: [&str; 6] = [
+    "B3/S23",
+    "B36/S23",
+    "B2/S",
+    "B3678/S34678",
+    "B1357/S1357",
+    "B368/S245",
+];
+
+/// Birth and survival conditions for a Life-like cellular automaton.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rule {
+    /// `birth[n]` is true if a dead cell with `n` live neighbors comes alive.
+    birth: [bool; 9],
+
+    /// `survival[n]` is true if a live cell with `n` live neighbors stays alive.
+    survival: [bool; 9],
+}
+
+impl Rule {
+    /// Parse a rule in B/S or S/B notation, ignoring case.
+    pub fn parse(text: &str) -> crate::Result<Rule> {
+        let text = text.trim();
+        let (first, second) = text
+            .split_once('/')
+            .ok_or_else(|| format!("bad rule: {text}"))?;
+
+        let upper = |s: &str| s.trim().to_ascii_uppercase();
+        let (first, second) = (upper(first), upper(second));
+
+        let (birth, survival) = if let Some(birth) = first.strip_prefix('B') {
+            let survival = second
+                .strip_prefix('S')
+                .ok_or_else(|| format!("bad rule: {text}"))?;
+            (birth.to_string(), survival.to_string())
+        } else if let Some(survival) = first.strip_prefix('S') {
+            let birth = second
+                .strip_prefix('B')
+                .ok_or_else(|| format!("bad rule: {text}"))?;
+            (birth.to_string(), survival.to_string())
+        } else {
+            // S/B notation without letters
+            (second, first)
+        };
+
+        Ok(Rule {
+            birth: Rule::parse_counts(&birth).ok_or_else(|| format!("bad rule: {text}"))?,
+            survival: Rule::parse_counts(&survival).ok_or_else(|| format!("bad rule: {text}"))?,
+        })
+    }
+
+    /// Parse a list of neighbor counts like `236`.
+    fn parse_counts(text: &str) -> Option<[bool; 9]> {
+        let mut counts = [false; 9];
+
+        for c in text.chars() {
+            let n = c.to_digit(10).filter(|n| *n <= 8)?;
+            counts[n as usize] = true;
+        }
+
+        Some(counts)
+    }
+
+    /// Work out whether a cell is alive next generation.
+    pub fn next_state(&self, alive: bool, neighbors: usize) -> bool {
+        if alive {
+            self.survival[neighbors]
+        } else {
+            self.birth[neighbors]
+        }
+    }
+}
+
+impl Default for Rule {
+    /// Create Conway's Life rule, `B3/S23`.
+    fn default() -> Self {
+        Rule::parse("B3/S23").unwrap()
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Write the rule in B/S notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |counts: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|n| counts[*n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+    }
+}
+
+/// How the edges of the grid are joined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology {
+    /// Left joins right and top joins bottom.
+    Torus,
+
+    /// Nothing is joined, so cells past the edges are always dead.
+    Plane,
+
+    /// Left joins right, and the top and bottom are edges.
+    Cylinder,
+
+    /// Left joins right, and top joins bottom mirrored left to right.
+    Klein,
+}
+
+impl Topology {
+    /// All the topologies.
+    pub const ALL: [Topology; 4] = [
+        Topology::Torus,
+        Topology::Plane,
+        Topology::Cylinder,
+        Topology::Klein,
+    ];
+
+    /// Get the name of the topology.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topology::Torus => "torus",
+            Topology::Plane => "plane",
+            Topology::Cylinder => "cylinder",
+            Topology::Klein => "klein",
+        }
+    }
+
+    /// Look up a topology by name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Topology> {
+        Topology::ALL
+            .into_iter()
+            .find(|topology| topology.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Find the cell `dx`, `dy` away from a cell, or `None` if that's off the edge.
+    ///
+    /// Offsets must be no more than one grid size. Stepping only looks one cell away, and the
+    /// census two, and grids are never smaller than that since they're at least as big as the
+    /// screen.
+    pub fn neighbor(
+        &self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize)> {
+        let nx = x as isize + dx;
+        let ny = y as isize + dy;
+        let (w, h) = (width as isize, height as isize);
+
+        let x_inside = (0..w).contains(&nx);
+        let y_inside = (0..h).contains(&ny);
+
+        match self {
+            Topology::Torus => Some((wrap(nx, w) as usize, wrap(ny, h) as usize)),
+
+            Topology::Plane => (x_inside && y_inside).then_some((nx as usize, ny as usize)),
+
+            Topology::Cylinder => y_inside.then_some((wrap(nx, w) as usize, ny as usize)),
+
+            Topology::Klein => {
+                let wx = wrap(nx, w);
+                let wx = if y_inside { wx } else { w - 1 - wx };
+                Some((wx as usize, wrap(ny, h) as usize))
+            }
+        }
+    }
+}
+
+/// Wrap a coordinate no more than one size off either edge back onto the grid.
+///
+/// This is on the hot path of every step, so it avoids the division in `rem_euclid`.
+fn wrap(n: isize, size: isize) -> isize {
+    debug_assert!(
+        (-size..size * 2).contains(&n),
+        "{n} is more than one size of {size} off the grid"
+    );
+
+    if n < 0 {
+        n + size
+    } else if n >= size {
+        n - size
+    } else {
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_b_s_notation() {
+        let rule = Rule::parse("B36/S23").unwrap();
+
+        assert!(rule.next_state(false, 3) && rule.next_state(false, 6));
+        assert!(!rule.next_state(false, 2));
+        assert!(rule.next_state(true, 2) && rule.next_state(true, 3));
+        assert!(!rule.next_state(true, 4));
+        assert_eq!(rule.to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn parses_s_b_notation_and_ignores_case() {
+        let life = Rule::default();
+
+        assert_eq!(Rule::parse("23/3").unwrap(), life);
+        assert_eq!(Rule::parse("S23/B3").unwrap(), life);
+        assert_eq!(Rule::parse(" b3 / s23 ").unwrap(), life);
+    }
+
+    #[test]
+    fn empty_counts_are_allowed() {
+        let seeds = Rule::parse("B2/S").unwrap();
+
+        assert_eq!(seeds.to_string(), "B2/S");
+        assert!((0..=8).all(|n| !seeds.next_state(true, n)));
+    }
+
+    #[test]
+    fn known_rules_read_back() {
+        for text in KNOWN_RULES {
+            assert_eq!(Rule::parse(text).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_rules() {
+        for text in [
+            "", "B3", "B3S23", "B9/S23", "B3/B23", "S23/S3", "B3/S2x", "B3/S23/", "life",
+        ] {
+            assert!(Rule::parse(text).is_err(), "{text:?} parsed");
+        }
+    }
+
+    #[test]
+    fn topology_names_read_back() {
+        for topology in Topology::ALL {
+            assert_eq!(Topology::from_name(topology.name()), Some(topology));
+        }
+
+        assert_eq!(Topology::from_name("KLEIN"), Some(Topology::Klein));
+        assert_eq!(Topology::from_name("sphere"), None);
+    }
+}
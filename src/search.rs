@@ -0,0 +1,403 @@
+//! Headless soup search
+//!
+//! Like [apgsearch](https://catagolue.hatsya.com/apgsearch), soups are made one after another from
+//! consecutive seeds, each is run until its population settles into a repeating cycle, and a
+//! census is taken of what's left. Every soup's objects go in a results file, objects are reported
+//! the first time they turn up, and the search ends with a summary of the rarest objects and the
+//! soups that made them.
+//!
+//! Each soup sits in the middle of a torus with a wide margin, so gliders and other spaceships
+//! have room to get away before they wrap around. Once in a while one comes back around and hits
+//! something before the soup settles, so a soup can turn out differently than on an endless grid.
+use crate::{
+    census::{Census, Kind, Names},
+    config::Config,
+    life::{Life, Stats},
+    rule::{Rule, Topology},
+    soup::{Soup, SoupOrigin, Symmetry},
+    util,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::Instant,
+};
+
+/// Dead cells on each side of a soup.
+const MARGIN: usize = 48;
+
+/// Most soups in one search.
+const MAX_SOUPS: u64 = 1_000_000_000;
+
+/// Most generations to run a soup for.
+const MAX_GENERATIONS: u64 = 1_000_000;
+
+/// Largest soup width or height.
+const MAX_SOUP_SIZE: usize = 256;
+
+/// Longest cycle of the population to wait for.
+const SETTLE_PERIOD: usize = 120;
+
+/// Generations the population has to keep repeating for a soup to count as settled.
+const SETTLE_GENERATIONS: usize = 300;
+
+/// Generations between checks for a soup settling.
+const SETTLE_INTERVAL: u64 = 50;
+
+/// Soups between progress reports.
+const PROGRESS_INTERVAL: u64 = 100;
+
+/// Rarest objects to list in the summary.
+const RAREST: usize = 20;
+
+/// Seeds to keep for each object, to find it again.
+const SAMPLE_SEEDS: usize = 5;
+
+/// Settings for a soup search.
+pub struct Search {
+    /// Number of soups to run.
+    soups: u64,
+
+    /// Seed of the first soup, with each soup after it using the next seed.
+    seed: u64,
+
+    /// Width of each soup.
+    width: usize,
+
+    /// Height of each soup.
+    height: usize,
+
+    /// Density and symmetry of the soups.
+    soup: Soup,
+
+    /// Rule to run the soups under.
+    rule: Rule,
+
+    /// Most generations to run a soup for before taking a census anyway.
+    generations: u64,
+
+    /// File to write the results to.
+    output: PathBuf,
+}
+
+/// All the objects of one kind found in the search.
+struct Tally {
+    /// Name of the object, or a description if it isn't a known one.
+    name: String,
+
+    /// What it does.
+    kind: Kind,
+
+    /// Live cells in its smallest phase.
+    cells: usize,
+
+    /// Number found.
+    count: usize,
+
+    /// First few seeds of soups it was found in.
+    seeds: Vec<u64>,
+}
+
+impl Search {
+    /// Read the search settings from the command line, starting from the config file's soup
+    /// settings and rule.
+    pub fn parse(args: impl IntoIterator<Item = String>, config: &Config) -> crate::Result<Search> {
+        let mut search = Search {
+            soups: 100,
+            seed: rand::random::<u32>() as u64,
+            width: 16,
+            height: 16,
+            soup: config.soup,
+            rule: config.rule,
+            generations: 10_000,
+            output: PathBuf::from(util::timestamped_file_name("rustlife-search", "txt")),
+        };
+
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or_else(|| format!("{arg} needs a value"))?;
+
+            match arg.as_str() {
+                "--soups" => search.soups = number(&arg, &value, 1, MAX_SOUPS)?,
+                "--seed" => search.seed = number(&arg, &value, 0, u64::MAX)?,
+                "--size" => {
+                    let (width, height) = value.split_once('x').unwrap_or((&value, &value));
+                    search.width = number(&arg, width, 1, MAX_SOUP_SIZE)?;
+                    search.height = number(&arg, height, 1, MAX_SOUP_SIZE)?;
+                }
+                "--density" => search.soup.density = number(&arg, &value, 0.0, 1.0)?,
+                "--symmetry" => {
+                    search.soup.symmetry = Symmetry::from_name(&value)
+                        .ok_or_else(|| format!("{arg}: unknown symmetry {value}"))?;
+                }
+                "--rule" => {
+                    search.rule = Rule::parse(&value).map_err(|err| format!("{arg}: {err}"))?;
+                }
+                "--generations" => {
+                    search.generations = number(&arg, &value, 1, MAX_GENERATIONS)?;
+                }
+                "--output" => search.output = PathBuf::from(value),
+                _ => return Err(format!("unknown argument {arg}").into()),
+            }
+        }
+
+        // The last seed can be `u64::MAX` itself, but not past it
+        let last_seed = search
+            .soups
+            .checked_sub(1)
+            .and_then(|n| search.seed.checked_add(n));
+
+        if last_seed.is_none() {
+            return Err("--seed: too big for that many soups".into());
+        }
+
+        Ok(search)
+    }
+
+    /// Run the search, writing each soup's objects to the results file and reporting progress on
+    /// the way.
+    pub fn run(&self) -> crate::Result<()> {
+        let path = self.output.display();
+        let file = File::create(&self.output).map_err(|err| format!("{path}: {err}"))?;
+        let mut out = BufWriter::new(file);
+
+        let origin = self.origin(self.seed);
+        let last_seed = self.seed + (self.soups - 1);
+        writeln!(
+            out,
+            "# Soup search under {} from seed {}",
+            self.rule, self.seed
+        )?;
+        writeln!(out, "# {}", origin.comment())?;
+        println!(
+            "Searching soups {} to {last_seed}, writing to {path}",
+            self.seed
+        );
+
+        let names = Names::new(&self.rule);
+        let mut life = Life::new();
+        let mut tallies: HashMap<(String, Kind, usize), Tally> = HashMap::new();
+        let mut objects = 0;
+        let started = Instant::now();
+
+        for (done, seed) in (self.seed..=last_seed).enumerate() {
+            let (census, settled) = self.run_soup(&mut life, &names, seed);
+            let mut found = Vec::new();
+
+            for row in census.rows {
+                objects += row.count;
+                found.push(format!("{} {}", row.count, row.name));
+
+                let tally = tallies
+                    .entry((row.name.clone(), row.kind, row.cells))
+                    .or_insert_with(|| {
+                        // Unknown objects are already named by what they are
+                        let kind = row.kind.description();
+
+                        if row.name.ends_with(&kind) {
+                            println!("Soup {seed}: first {}", row.name);
+                        } else {
+                            println!("Soup {seed}: first {} ({kind})", row.name);
+                        }
+
+                        Tally {
+                            name: row.name,
+                            kind: row.kind,
+                            cells: row.cells,
+                            count: 0,
+                            seeds: Vec::new(),
+                        }
+                    });
+
+                tally.count += row.count;
+
+                if tally.seeds.len() < SAMPLE_SEEDS {
+                    tally.seeds.push(seed);
+                }
+            }
+
+            if found.is_empty() {
+                found.push("nothing".to_string());
+            }
+
+            if !settled {
+                found.push(format!("unsettled at generation {}", self.generations));
+            }
+
+            writeln!(out, "{seed}  {}", found.join(", "))?;
+
+            let done = done as u64 + 1;
+
+            if done.is_multiple_of(PROGRESS_INTERVAL) && done < self.soups {
+                println!(
+                    "{done} soups, {objects} objects, {:.1} soups per second",
+                    done as f64 / started.elapsed().as_secs_f64()
+                );
+            }
+        }
+
+        let mut tallies: Vec<Tally> = tallies.into_values().collect();
+        tallies.sort_by(|a, b| {
+            a.count
+                .cmp(&b.count)
+                .then(b.cells.cmp(&a.cells))
+                .then(a.name.cmp(&b.name))
+        });
+        tallies.truncate(RAREST);
+
+        let summary = self.summary(&tallies, objects, started.elapsed().as_secs_f64());
+        print!("\n{summary}");
+
+        writeln!(out, "\n{summary}")?;
+        writeln!(out, "# Soups the rarest objects were first found in")?;
+
+        for tally in &tallies {
+            let seed = tally.seeds[0];
+            let soup = self.soup.pattern(self.width, self.height, seed);
+            let comments = [tally.name.clone(), self.origin(seed).comment()];
+            writeln!(out, "\n{}", soup.to_rle(&self.rule, &comments).trim_end())?;
+        }
+
+        out.flush().map_err(|err| format!("{path}: {err}"))?;
+
+        Ok(())
+    }
+
+    /// Run one soup until it settles or runs out of generations, then take a census.
+    ///
+    /// Returns the census and whether the soup settled.
+    fn run_soup(&self, life: &mut Life, names: &Names, seed: u64) -> (Census, bool) {
+        life.init(self.width + MARGIN * 2, self.height + MARGIN * 2);
+        life.set_rule(self.rule);
+        life.set_topology(Topology::Torus);
+        life.randomize(MARGIN, MARGIN, self.width, self.height, &self.soup, seed);
+        life.set_generation(0);
+
+        let mut settled = false;
+
+        while life.generation() < self.generations {
+            life.step();
+
+            if life.generation().is_multiple_of(SETTLE_INTERVAL) && is_settled(life.history()) {
+                settled = true;
+                break;
+            }
+        }
+
+        (Census::take_named(life, names), settled)
+    }
+
+    /// Describe how a soup was made.
+    fn origin(&self, seed: u64) -> SoupOrigin {
+        SoupOrigin {
+            soup: self.soup,
+            seed,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Write a table of the rarest objects found.
+    fn summary(&self, rarest: &[Tally], objects: usize, seconds: f64) -> String {
+        let name_width = rarest
+            .iter()
+            .map(|tally| tally.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Object".len());
+
+        let kind_width = rarest
+            .iter()
+            .map(|tally| tally.kind.description().len())
+            .max()
+            .unwrap_or(0)
+            .max("Kind".len());
+
+        let mut text = format!(
+            "{} soups, {objects} objects, in {seconds:.1} seconds\n\nRarest objects:\n\n\
+             {:>7}  {:name_width$}  {:>5}  {:kind_width$}  Seeds\n",
+            self.soups, "Count", "Object", "Cells", "Kind",
+        );
+
+        for tally in rarest {
+            let seeds: Vec<String> = tally.seeds.iter().map(u64::to_string).collect();
+
+            text.push_str(&format!(
+                "{:>7}  {:name_width$}  {:>5}  {:kind_width$}  {}\n",
+                tally.count,
+                tally.name,
+                tally.cells,
+                tally.kind.description(),
+                seeds.join(" ")
+            ));
+        }
+
+        text
+    }
+}
+
+/// Check if the population has been going around the same cycle long enough to call a soup
+/// settled.
+///
+/// Spaceships flying off keep the population steady, so this works even though the cells
+/// themselves never repeat.
+fn is_settled(history: &VecDeque<Stats>) -> bool {
+    let populations: Vec<usize> = history
+        .iter()
+        .rev()
+        .take(SETTLE_GENERATIONS + SETTLE_PERIOD)
+        .map(|stats| stats.population)
+        .collect();
+
+    (1..=SETTLE_PERIOD).any(|period| {
+        populations.len() >= SETTLE_GENERATIONS + period
+            && (0..SETTLE_GENERATIONS).all(|i| populations[i] == populations[i + period])
+    })
+}
+
+/// Parse a number from the command line that has to be in a range.
+fn number<T>(arg: &str, value: &str, min: T, max: T) -> crate::Result<T>
+where
+    T: FromStr + PartialOrd + std::fmt::Display,
+{
+    match value.parse::<T>() {
+        Ok(number) if number >= min && number <= max => Ok(number),
+        Ok(_) => Err(format!("{arg}: must be {min} to {max}").into()),
+        Err(_) => Err(format!("{arg}: not a number: {value}").into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse search arguments with the default config.
+    fn parse(args: &[&str]) -> crate::Result<Search> {
+        Search::parse(args.iter().map(|arg| arg.to_string()), &Config::default())
+    }
+
+    #[test]
+    fn last_seed_can_be_the_largest() {
+        let max = u64::MAX.to_string();
+        let search = parse(&["--seed", &max, "--soups", "1"]).unwrap();
+        assert_eq!(search.seed, u64::MAX);
+
+        let second_last = (u64::MAX - 1).to_string();
+        assert!(parse(&["--seed", &second_last, "--soups", "2"]).is_ok());
+        assert!(parse(&["--seed", &second_last, "--soups", "3"]).is_err());
+        assert!(parse(&["--seed", &max, "--soups", "2"]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse(&["--soups", "0"]).is_err());
+        assert!(parse(&["--soups"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--rule", "B9/S"]).is_err());
+        assert!(parse(&["--frobnicate", "1"]).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+//! Saved sessions
+//!
+//! When quitting, the universe and how it was being looked at are saved to
+//! `rustlife/session.toml` in the [XDG state
+//! directory](https://specifications.freedesktop.org/basedir-spec/latest/), which is usually
+//! `~/.local/state`, and put back the next time rustlife starts. The cells are kept as RLE, which
+//! also records the rule.
+use crate::{
+    pattern::Pattern,
+    rule::{Rule, Topology},
+};
+use serde::{Deserialize, Serialize};
+use std::{env, fs, io, path::PathBuf};
+
+/// The session file as written.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct SessionFile {
+    generation: u64,
+    topology: String,
+    speed: u32,
+    cursor: (usize, usize),
+    viewport: (usize, usize),
+    zoom: usize,
+    cells: String,
+}
+
+/// Everything needed to carry on where the last run left off.
+pub struct Session {
+    /// The whole universe, including its dead edges.
+    pub pattern: Pattern,
+
+    /// Rule the cells follow.
+    pub rule: Rule,
+
+    /// How the edges of the universe are joined.
+    pub topology: Topology,
+
+    /// Generations since the universe was started.
+    pub generation: u64,
+
+    /// Generations per second when running.
+    pub speed: u32,
+
+    /// Cell the cursor is on.
+    pub cursor: (usize, usize),
+
+    /// Universe coordinates of the top-left cell in view.
+    pub viewport: (usize, usize),
+
+    /// Index into the zoom levels.
+    pub zoom: usize,
+}
+
+impl Session {
+    /// Get the path of the session file, if there's a home directory to put it in.
+    pub fn path() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::home_dir().map(|home| home.join(".local").join("state")))?;
+
+        Some(dir.join("rustlife").join("session.toml"))
+    }
+
+    /// Load the last session, or `None` if there isn't one.
+    pub fn load() -> crate::Result<Option<Session>> {
+        let Some(path) = Session::path() else {
+            return Ok(None);
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => Session::parse(&text)
+                .map(Some)
+                .map_err(|err| format!("{}: {err}", path.display()).into()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("{}: {err}", path.display()).into()),
+        }
+    }
+
+    /// Save the session, making its directory if needed.
+    pub fn save(&self) -> crate::Result<()> {
+        let path = Session::path().ok_or("no home directory for the session file")?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+        }
+
+        let file = SessionFile {
+            generation: self.generation,
+            topology: self.topology.name().to_string(),
+            speed: self.speed,
+            cursor: self.cursor,
+            viewport: self.viewport,
+            zoom: self.zoom,
+            cells: self.pattern.to_rle(&self.rule, &[]),
+        };
+
+        fs::write(&path, toml::to_string(&file)?)
+            .map_err(|err| format!("{}: {err}", path.display()).into())
+    }
+
+    /// Parse the text of a session file.
+    fn parse(text: &str) -> crate::Result<Session> {
+        let file: SessionFile = toml::from_str(text)?;
+
+        Ok(Session {
+            pattern: Pattern::from_rle(&file.cells)?,
+            rule: Pattern::rle_rule(&file.cells).unwrap_or(Ok(Rule::default()))?,
+            topology: Topology::from_name(&file.topology)
+                .ok_or_else(|| format!("unknown topology {:?}", file.topology))?,
+            generation: file.generation,
+            speed: file.speed,
+            cursor: file.cursor,
+            viewport: file.viewport,
+            zoom: file.zoom,
+        })
+    }
+}
@@ -0,0 +1,148 @@
+//! Drawing shapes
+//!
+//! Shapes are drawn from an anchor cell to the cursor and turned into a [`Pattern`] so they can be
+//! previewed and pasted like anything else.
+use crate::{life::LifeCell, pattern::Pattern};
+
+/// A shape to draw.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// Straight line from the anchor to the cursor.
+    Line,
+
+    /// Outline of a rectangle with the anchor and cursor at opposite corners.
+    Rectangle,
+
+    /// Solid rectangle with the anchor and cursor at opposite corners.
+    FilledRectangle,
+
+    /// Outline of an ellipse that fits in the rectangle.
+    Ellipse,
+
+    /// Solid ellipse that fits in the rectangle.
+    FilledEllipse,
+}
+
+impl Shape {
+    /// Get the next shape in the cycle.
+    pub fn next(self) -> Self {
+        match self {
+            Shape::Line => Shape::Rectangle,
+            Shape::Rectangle => Shape::FilledRectangle,
+            Shape::FilledRectangle => Shape::Ellipse,
+            Shape::Ellipse => Shape::FilledEllipse,
+            Shape::FilledEllipse => Shape::Line,
+        }
+    }
+
+    /// Get the name of the shape for the status bar.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Line => "LINE",
+            Shape::Rectangle => "RECT",
+            Shape::FilledRectangle => "FILLED RECT",
+            Shape::Ellipse => "ELLIPSE",
+            Shape::FilledEllipse => "FILLED ELLIPSE",
+        }
+    }
+
+    /// Draw the shape from the anchor to a cursor `dx`, `dy` cells away.
+    ///
+    /// Returns the pattern along with the offset of its top-left corner from the anchor.
+    pub fn pattern(&self, dx: isize, dy: isize) -> (Pattern, isize, isize) {
+        let width = dx.unsigned_abs() + 1;
+        let height = dy.unsigned_abs() + 1;
+        let left = dx.min(0);
+        let top = dy.min(0);
+
+        let mut pattern = Pattern::new(width, height);
+
+        match self {
+            Shape::Line => {
+                for (x, y) in line_cells(-left, -top, dx - left, dy - top) {
+                    pattern.set_cell(x as usize, y as usize, LifeCell::Alive);
+                }
+            }
+
+            Shape::Rectangle | Shape::FilledRectangle => {
+                let filled = *self == Shape::FilledRectangle;
+
+                for y in 0..height {
+                    for x in 0..width {
+                        let edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+
+                        if filled || edge {
+                            pattern.set_cell(x, y, LifeCell::Alive);
+                        }
+                    }
+                }
+            }
+
+            Shape::Ellipse | Shape::FilledEllipse => {
+                let filled = *self == Shape::FilledEllipse;
+
+                // Inside if the cell's center is inside the ellipse touching the rectangle's edges
+                let inside = |x: isize, y: isize| {
+                    let nx = (x as f64 + 0.5) / width as f64 * 2.0 - 1.0;
+                    let ny = (y as f64 + 0.5) / height as f64 * 2.0 - 1.0;
+
+                    x >= 0
+                        && y >= 0
+                        && x < width as isize
+                        && y < height as isize
+                        && nx * nx + ny * ny <= 1.0
+                };
+
+                for y in 0..height as isize {
+                    for x in 0..width as isize {
+                        // The outline is the inside cells that touch the outside
+                        let edge = !inside(x - 1, y)
+                            || !inside(x + 1, y)
+                            || !inside(x, y - 1)
+                            || !inside(x, y + 1);
+
+                        if inside(x, y) && (filled || edge) {
+                            pattern.set_cell(x as usize, y as usize, LifeCell::Alive);
+                        }
+                    }
+                }
+            }
+        }
+
+        (pattern, left, top)
+    }
+}
+
+/// Get the cells on a line between two points with Bresenham's algorithm.
+pub fn line_cells(x0: isize, y0: isize, x1: isize, y1: isize) -> Vec<(isize, isize)> {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+
+    let (mut x, mut y) = (x0, y0);
+    let mut err = dx + dy;
+    let mut cells = Vec::new();
+
+    loop {
+        cells.push((x, y));
+
+        if x == x1 && y == y1 {
+            break;
+        }
+
+        let e2 = 2 * err;
+
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+
+    cells
+}
@@ -0,0 +1,322 @@
+//! Random soups
+//!
+//! A soup is a random starting pattern. Its cells are alive with a given probability, optionally
+//! with a symmetry like the soups on [Catagolue](https://catagolue.hatsya.com/), and they come
+//! from a seeded RNG so the same seed always makes the same soup.
+use rand::{RngExt, SeedableRng, rngs::Xoshiro256PlusPlus};
+
+use crate::{life::LifeCell, pattern::Pattern};
+
+/// Symmetry of a soup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    /// No symmetry.
+    C1,
+
+    /// Same when turned 180°.
+    C2,
+
+    /// Same when turned 90°.
+    C4,
+
+    /// Mirrored left to right.
+    D2,
+
+    /// Mirrored left to right and top to bottom.
+    D4,
+
+    /// Mirrored left to right, top to bottom, and across the diagonals.
+    D8,
+}
+
+impl Symmetry {
+    /// All the symmetries, in cycle order.
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::C1,
+        Symmetry::C2,
+        Symmetry::C4,
+        Symmetry::D2,
+        Symmetry::D4,
+        Symmetry::D8,
+    ];
+
+    /// Get the short name of the symmetry.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D2 => "D2",
+            Symmetry::D4 => "D4",
+            Symmetry::D8 => "D8",
+        }
+    }
+
+    /// Get a description of the symmetry.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Symmetry::C1 => "none",
+            Symmetry::C2 => "180° turn",
+            Symmetry::C4 => "90° turn",
+            Symmetry::D2 => "one mirror",
+            Symmetry::D4 => "two mirrors",
+            Symmetry::D8 => "four mirrors",
+        }
+    }
+
+    /// Look up a symmetry by name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Symmetry> {
+        Symmetry::ALL
+            .into_iter()
+            .find(|symmetry| symmetry.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Get the next symmetry in the cycle.
+    pub fn next(self) -> Self {
+        let i = Symmetry::ALL.iter().position(|s| *s == self).unwrap();
+        Symmetry::ALL[(i + 1) % Symmetry::ALL.len()]
+    }
+
+    /// Get the previous symmetry in the cycle.
+    pub fn prev(self) -> Self {
+        let i = Symmetry::ALL.iter().position(|s| *s == self).unwrap();
+        Symmetry::ALL[(i + Symmetry::ALL.len() - 1) % Symmetry::ALL.len()]
+    }
+
+    /// Check if the symmetry only works on a square.
+    fn needs_square(&self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+
+    /// Get the cell whose random value a cell copies, so the soup has the symmetry.
+    ///
+    /// Every cell in a symmetric set picks the same one, the smallest in row-major order.
+    fn source(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (rx, ry) = (width - 1 - x, height - 1 - y);
+
+        let images: &[(usize, usize)] = match self {
+            Symmetry::C1 => &[(x, y)],
+            Symmetry::C2 => &[(x, y), (rx, ry)],
+            Symmetry::C4 => &[(x, y), (ry, x), (rx, ry), (y, rx)],
+            Symmetry::D2 => &[(x, y), (rx, y)],
+            Symmetry::D4 => &[(x, y), (rx, y), (x, ry), (rx, ry)],
+            Symmetry::D8 => &[
+                (x, y),
+                (ry, x),
+                (rx, ry),
+                (y, rx),
+                (rx, y),
+                (x, ry),
+                (y, x),
+                (ry, rx),
+            ],
+        };
+
+        images.iter().copied().min_by_key(|&(x, y)| (y, x)).unwrap()
+    }
+}
+
+/// Settings for making soups.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Soup {
+    /// Chance of each cell being alive, from `0.0` to `1.0`.
+    pub density: f64,
+
+    /// Symmetry of the soup.
+    pub symmetry: Symmetry,
+}
+
+impl Soup {
+    /// Make a soup pattern from a seed.
+    ///
+    /// Symmetries that turn by 90° or mirror across the diagonals need a square, so they fill the
+    /// largest square that fits in the middle and leave the rest dead.
+    pub fn pattern(&self, width: usize, height: usize, seed: u64) -> Pattern {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+        let mut pattern = Pattern::new(width, height);
+
+        let (soup_w, soup_h) = if self.symmetry.needs_square() {
+            (width.min(height), width.min(height))
+        } else {
+            (width, height)
+        };
+
+        let left = (width - soup_w) / 2;
+        let top = (height - soup_h) / 2;
+
+        let random: Vec<Vec<bool>> = (0..soup_h)
+            .map(|_| {
+                (0..soup_w)
+                    .map(|_| rng.random_bool(self.density.clamp(0.0, 1.0)))
+                    .collect()
+            })
+            .collect();
+
+        for y in 0..soup_h {
+            for x in 0..soup_w {
+                let (sx, sy) = self.symmetry.source(x, y, soup_w, soup_h);
+
+                if random[sy][sx] {
+                    pattern.set_cell(left + x, top + y, LifeCell::Alive);
+                }
+            }
+        }
+
+        pattern
+    }
+}
+
+/// How a soup was made, so it can be made again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoupOrigin {
+    /// Settings the soup was made with.
+    pub soup: Soup,
+
+    /// Seed for the RNG.
+    pub seed: u64,
+
+    /// Width of the region the soup filled.
+    pub width: usize,
+
+    /// Height of the region the soup filled.
+    pub height: usize,
+}
+
+impl SoupOrigin {
+    /// Describe the soup for a comment in a saved pattern.
+    pub fn comment(&self) -> String {
+        format!(
+            "Random soup seed {} on {}x{} at {:.1}% density with {} symmetry",
+            self.seed,
+            self.width,
+            self.height,
+            self.soup.density * 100.0,
+            self.soup.symmetry.name()
+        )
+    }
+}
+
+impl Default for Soup {
+    /// Create the default soup settings, half full with no symmetry.
+    fn default() -> Self {
+        Self {
+            density: 0.5,
+            symmetry: Symmetry::C1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::Transform;
+
+    /// Make a soup with a symmetry.
+    fn soup(symmetry: Symmetry, width: usize, height: usize, seed: u64) -> Pattern {
+        Soup {
+            density: 0.5,
+            symmetry,
+        }
+        .pattern(width, height, seed)
+    }
+
+    /// Check that a pattern is the same after each of some transforms.
+    fn is_symmetric(pattern: &Pattern, transforms: &[Transform]) -> bool {
+        transforms
+            .iter()
+            .all(|transform| pattern.transformed(*transform) == *pattern)
+    }
+
+    #[test]
+    fn soups_have_their_symmetry() {
+        use Transform::*;
+
+        // Odd and even sizes, since the middle row or column is its own mirror image
+        for (width, height) in [(9, 9), (10, 10), (10, 7)] {
+            for seed in 0..10 {
+                let check = |symmetry, transforms: &[Transform]| {
+                    let pattern = soup(symmetry, width, height, seed);
+                    assert!(
+                        is_symmetric(&pattern, transforms),
+                        "{symmetry:?} seed {seed}"
+                    );
+                };
+
+                check(Symmetry::C2, &[Rotate180]);
+                check(Symmetry::D2, &[FlipHorizontal]);
+                check(Symmetry::D4, &[FlipHorizontal, FlipVertical]);
+            }
+        }
+
+        for size in [9, 10] {
+            for seed in 0..10 {
+                let c4 = soup(Symmetry::C4, size, size, seed);
+                let d8 = soup(Symmetry::D8, size, size, seed);
+
+                assert!(is_symmetric(&c4, &[RotateCw]));
+                assert!(is_symmetric(&d8, &[RotateCw, FlipHorizontal, Transpose]));
+            }
+        }
+    }
+
+    #[test]
+    fn symmetries_are_no_stronger_than_asked() {
+        // A soup with one symmetry can happen to have more, but not for every seed
+        let sometimes_not = |symmetry, transform| {
+            (0..10).any(|seed| !is_symmetric(&soup(symmetry, 8, 8, seed), &[transform]))
+        };
+
+        assert!(sometimes_not(Symmetry::C1, Transform::Rotate180));
+        assert!(sometimes_not(Symmetry::C2, Transform::RotateCw));
+        assert!(sometimes_not(Symmetry::D2, Transform::FlipVertical));
+        assert!(sometimes_not(Symmetry::D4, Transform::Transpose));
+        assert!(sometimes_not(Symmetry::C4, Transform::FlipHorizontal));
+    }
+
+    #[test]
+    fn square_symmetries_fill_the_middle_square() {
+        let pattern = soup(Symmetry::C4, 12, 6, 1);
+        let mut square = Pattern::new(6, 6);
+
+        for y in 0..6 {
+            for x in 0..12 {
+                if (3..9).contains(&x) {
+                    square.set_cell(x - 3, y, pattern.get_cell(x, y));
+                } else {
+                    assert_eq!(pattern.get_cell(x, y), LifeCell::Dead);
+                }
+            }
+        }
+
+        assert!(square.population() > 0);
+        assert!(is_symmetric(&square, &[Transform::RotateCw]));
+    }
+
+    #[test]
+    fn same_seed_makes_the_same_soup() {
+        assert_eq!(
+            soup(Symmetry::C1, 20, 10, 42),
+            soup(Symmetry::C1, 20, 10, 42)
+        );
+        assert_ne!(
+            soup(Symmetry::C1, 20, 10, 42),
+            soup(Symmetry::C1, 20, 10, 43)
+        );
+    }
+
+    #[test]
+    fn density_is_the_chance_of_being_alive() {
+        let with_density = |density| {
+            Soup {
+                density,
+                symmetry: Symmetry::C1,
+            }
+            .pattern(10, 10, 0)
+            .population()
+        };
+
+        assert_eq!(with_density(0.0), 0);
+        assert_eq!(with_density(1.0), 100);
+        assert_eq!(with_density(2.0), 100);
+    }
+}
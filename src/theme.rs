@@ -0,0 +1,433 @@
+//! Color themes
+//!
+//! Themes are defined in 24-bit RGB and converted to whatever the terminal supports when the
+//! [`Theme`] is built. Setting `NO_COLOR` turns off color entirely, per https://no-color.org/.
+use ratatui::style::Color;
+use std::env;
+
+/// Number of generations a dead cell leaves a ghost trail.
+pub const TRAIL_LENGTH: u16 = 8;
+
+/// Number of age color buckets: ages 0, 1, 2-3, 4-7, 8-15, 16-31, and 32 and up.
+pub const AGE_BUCKETS: usize = 7;
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorDepth {
+    /// No color at all.
+    None,
+
+    /// The 16 basic ANSI colors.
+    Ansi16,
+
+    /// The 256-color xterm palette.
+    Indexed256,
+
+    /// 24-bit color.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Guess the color depth from the environment.
+    pub fn detect() -> Self {
+        if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return ColorDepth::None;
+        }
+
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+
+        let term = env::var("TERM").unwrap_or_default();
+
+        if term.contains("256color") {
+            ColorDepth::Indexed256
+        } else if term == "dumb" {
+            ColorDepth::None
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// Convert an RGB color to the closest one available.
+    pub fn color(&self, (r, g, b): (u8, u8, u8)) -> Color {
+        match self {
+            ColorDepth::None => Color::Reset,
+            ColorDepth::Ansi16 => nearest_ansi16(r, g, b),
+            ColorDepth::Indexed256 => nearest_indexed(r, g, b),
+            ColorDepth::TrueColor => Color::Rgb(r, g, b),
+        }
+    }
+}
+
+/// The built-in themes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThemeKind {
+    Classic,
+    Amber,
+    HighContrast,
+    Monochrome,
+}
+
+impl ThemeKind {
+    /// All the built-in themes, in cycle order.
+    pub const ALL: [ThemeKind; 4] = [
+        ThemeKind::Classic,
+        ThemeKind::Amber,
+        ThemeKind::HighContrast,
+        ThemeKind::Monochrome,
+    ];
+
+    /// Get the name of the theme.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeKind::Classic => "classic",
+            ThemeKind::Amber => "amber",
+            ThemeKind::HighContrast => "high-contrast",
+            ThemeKind::Monochrome => "monochrome",
+        }
+    }
+
+    /// Look up a theme by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// Get the next theme in the cycle.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|kind| *kind == self).unwrap();
+
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Get the RGB palette for this theme.
+    fn palette(&self) -> Palette {
+        match self {
+            ThemeKind::Classic => Palette {
+                alive_glyph: '▓',
+                dead_glyph: ' ',
+                alive: (175, 255, 175),
+                dead: (58, 58, 58),
+                ages: [
+                    (255, 255, 255),
+                    (255, 255, 135),
+                    (175, 255, 175),
+                    (135, 255, 215),
+                    (95, 215, 255),
+                    (95, 135, 255),
+                    (135, 95, 215),
+                ],
+                birth: (95, 175, 95),
+                death: (175, 95, 95),
+                ghost_new: (208, 208, 208),
+                ghost_old: (58, 58, 58),
+                border: None,
+                grid: (58, 58, 58),
+                cursor: None,
+                selection: (0, 95, 135),
+                dialog_border: None,
+                dialog_key: (175, 175, 255),
+                dialog_dim: (168, 168, 168),
+            },
+
+            ThemeKind::Amber => Palette {
+                alive_glyph: '█',
+                dead_glyph: ' ',
+                alive: (255, 176, 0),
+                dead: (50, 30, 0),
+                ages: [
+                    (255, 240, 160),
+                    (255, 215, 100),
+                    (255, 190, 40),
+                    (255, 165, 0),
+                    (215, 135, 0),
+                    (175, 100, 0),
+                    (135, 75, 0),
+                ],
+                birth: (175, 120, 40),
+                death: (120, 75, 0),
+                ghost_new: (120, 75, 0),
+                ghost_old: (50, 30, 0),
+                border: Some((215, 135, 0)),
+                grid: (80, 50, 0),
+                cursor: Some((120, 75, 0)),
+                selection: (95, 60, 0),
+                dialog_border: Some((255, 176, 0)),
+                dialog_key: (255, 215, 100),
+                dialog_dim: (175, 120, 40),
+            },
+
+            ThemeKind::HighContrast => Palette {
+                alive_glyph: '█',
+                dead_glyph: ' ',
+                alive: (255, 255, 255),
+                dead: (88, 88, 88),
+                ages: [
+                    (255, 255, 255),
+                    (255, 255, 0),
+                    (0, 255, 0),
+                    (0, 255, 255),
+                    (0, 135, 255),
+                    (255, 0, 255),
+                    (255, 0, 0),
+                ],
+                birth: (0, 175, 0),
+                death: (175, 0, 0),
+                ghost_new: (175, 175, 175),
+                ghost_old: (88, 88, 88),
+                border: Some((255, 255, 0)),
+                grid: (128, 128, 128),
+                cursor: Some((255, 0, 255)),
+                selection: (0, 0, 255),
+                dialog_border: Some((255, 255, 0)),
+                dialog_key: (255, 255, 0),
+                dialog_dim: (255, 255, 255),
+            },
+
+            // Colors don't matter here since they're never used
+            ThemeKind::Monochrome => Palette {
+                alive_glyph: '█',
+                dead_glyph: ' ',
+                alive: (255, 255, 255),
+                dead: (255, 255, 255),
+                ages: [(255, 255, 255); AGE_BUCKETS],
+                birth: (255, 255, 255),
+                death: (255, 255, 255),
+                ghost_new: (255, 255, 255),
+                ghost_old: (255, 255, 255),
+                border: None,
+                grid: (255, 255, 255),
+                cursor: None,
+                selection: (255, 255, 255),
+                dialog_border: None,
+                dialog_key: (255, 255, 255),
+                dialog_dim: (255, 255, 255),
+            },
+        }
+    }
+}
+
+/// Theme colors in RGB, before conversion for the terminal.
+struct Palette {
+    alive_glyph: char,
+    dead_glyph: char,
+    alive: (u8, u8, u8),
+    dead: (u8, u8, u8),
+    ages: [(u8, u8, u8); AGE_BUCKETS],
+    birth: (u8, u8, u8),
+    death: (u8, u8, u8),
+    ghost_new: (u8, u8, u8),
+    ghost_old: (u8, u8, u8),
+    border: Option<(u8, u8, u8)>,
+    grid: (u8, u8, u8),
+    cursor: Option<(u8, u8, u8)>,
+    selection: (u8, u8, u8),
+    dialog_border: Option<(u8, u8, u8)>,
+    dialog_key: (u8, u8, u8),
+    dialog_dim: (u8, u8, u8),
+}
+
+/// A theme, with colors ready to use on this terminal.
+pub struct Theme {
+    /// Which built-in theme this is.
+    pub kind: ThemeKind,
+
+    /// How many colors are in use.
+    pub depth: ColorDepth,
+
+    /// Glyph for a live cell at normal zoom.
+    pub alive_glyph: char,
+
+    /// Glyph for a dead cell at normal zoom.
+    pub dead_glyph: char,
+
+    /// Color for live cells.
+    pub alive: Color,
+
+    /// Color for dead cells.
+    pub dead: Color,
+
+    /// Colors for live cells by age bucket.
+    pub ages: [Color; AGE_BUCKETS],
+
+    /// Color for dead cells that will be born next generation.
+    pub birth: Color,
+
+    /// Color for live cells that will die next generation.
+    pub death: Color,
+
+    /// Colors for dead cells by the number of generations since they died.
+    pub ghosts: [Color; TRAIL_LENGTH as usize],
+
+    /// Color for window borders.
+    pub border: Color,
+
+    /// Color for gridlines.
+    pub grid: Color,
+
+    /// Background color for the cell under the cursor, if it's highlighted.
+    pub cursor: Option<Color>,
+
+    /// Background color for selected cells.
+    pub selection: Color,
+
+    /// Color for dialog borders.
+    pub dialog_border: Color,
+
+    /// Color for highlighted text in dialogs, like key names.
+    pub dialog_key: Color,
+
+    /// Color for de-emphasized text in dialogs.
+    pub dialog_dim: Color,
+}
+
+impl Theme {
+    /// Build a theme for the current terminal.
+    pub fn new(kind: ThemeKind) -> Self {
+        let depth = match kind {
+            ThemeKind::Monochrome => ColorDepth::None,
+            _ => ColorDepth::detect(),
+        };
+
+        Self::with_depth(kind, depth)
+    }
+
+    /// Build a theme for a given color depth.
+    pub fn with_depth(kind: ThemeKind, depth: ColorDepth) -> Self {
+        let palette = kind.palette();
+        let color = |rgb| depth.color(rgb);
+        let optional = |rgb: Option<(u8, u8, u8)>| rgb.map_or(Color::Reset, color);
+
+        let ghosts = std::array::from_fn(|i| {
+            let (r0, g0, b0) = palette.ghost_new;
+            let (r1, g1, b1) = palette.ghost_old;
+            let lerp = |a: u8, b: u8| {
+                (a as usize * (TRAIL_LENGTH as usize - 1 - i) + b as usize * i)
+                    / (TRAIL_LENGTH as usize - 1)
+            };
+
+            color((lerp(r0, r1) as u8, lerp(g0, g1) as u8, lerp(b0, b1) as u8))
+        });
+
+        Self {
+            kind,
+            depth,
+            alive_glyph: palette.alive_glyph,
+            dead_glyph: palette.dead_glyph,
+            alive: color(palette.alive),
+            dead: color(palette.dead),
+            ages: palette.ages.map(color),
+            birth: color(palette.birth),
+            death: color(palette.death),
+            ghosts,
+            border: optional(palette.border),
+            grid: color(palette.grid),
+            cursor: palette
+                .cursor
+                .filter(|_| depth != ColorDepth::None)
+                .map(color),
+            selection: color(palette.selection),
+            dialog_border: optional(palette.dialog_border),
+            dialog_key: color(palette.dialog_key),
+            dialog_dim: color(palette.dialog_dim),
+        }
+    }
+
+    /// Replace the glyphs and colors for live and dead cells with custom ones, where given.
+    pub fn with_cells(
+        mut self,
+        alive_glyph: Option<char>,
+        dead_glyph: Option<char>,
+        alive: Option<(u8, u8, u8)>,
+        dead: Option<(u8, u8, u8)>,
+    ) -> Self {
+        self.alive_glyph = alive_glyph.unwrap_or(self.alive_glyph);
+        self.dead_glyph = dead_glyph.unwrap_or(self.dead_glyph);
+        self.alive = alive.map_or(self.alive, |rgb| self.depth.color(rgb));
+        self.dead = dead.map_or(self.dead, |rgb| self.depth.color(rgb));
+        self
+    }
+
+    /// Get the color for a live cell of a given age.
+    ///
+    /// Ages are bucketed by powers of two, so newborn cells stand out from stable ones.
+    pub fn age_color(&self, age: u16) -> Color {
+        let bucket = ((u16::BITS - age.leading_zeros()) as usize).min(AGE_BUCKETS - 1);
+
+        self.ages[bucket]
+    }
+
+    /// Get the color for a dead cell that died a given number of generations ago.
+    pub fn ghost_color(&self, age: u16) -> Color {
+        self.ghosts[(age as usize).min(TRAIL_LENGTH as usize - 1)]
+    }
+}
+
+impl Default for Theme {
+    /// Build the classic theme for the current terminal.
+    fn default() -> Self {
+        Self::new(ThemeKind::Classic)
+    }
+}
+
+/// Find the closest color in the 256-color xterm palette.
+fn nearest_indexed(r: u8, g: u8, b: u8) -> Color {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let nearest_level = |v: u8| {
+        (0..LEVELS.len())
+            .min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs())
+            .unwrap()
+    };
+
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (LEVELS[ri], LEVELS[gi], LEVELS[bi]);
+
+    // The gray ramp is 24 shades from 8 to 238
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_i = ((avg.saturating_sub(3)) / 10).min(23) as u8;
+    let gray_v = 8 + 10 * gray_i;
+    let gray = (gray_v, gray_v, gray_v);
+
+    if distance((r, g, b), gray) < distance((r, g, b), cube) {
+        Color::Indexed(232 + gray_i)
+    } else {
+        Color::Indexed(16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8)
+    }
+}
+
+/// Find the closest of the 16 basic ANSI colors.
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    const ANSI: [(Color, (u8, u8, u8)); 16] = [
+        (Color::Black, (0, 0, 0)),
+        (Color::Red, (170, 0, 0)),
+        (Color::Green, (0, 170, 0)),
+        (Color::Yellow, (170, 85, 0)),
+        (Color::Blue, (0, 0, 170)),
+        (Color::Magenta, (170, 0, 170)),
+        (Color::Cyan, (0, 170, 170)),
+        (Color::Gray, (170, 170, 170)),
+        (Color::DarkGray, (85, 85, 85)),
+        (Color::LightRed, (255, 85, 85)),
+        (Color::LightGreen, (85, 255, 85)),
+        (Color::LightYellow, (255, 255, 85)),
+        (Color::LightBlue, (85, 85, 255)),
+        (Color::LightMagenta, (255, 85, 255)),
+        (Color::LightCyan, (85, 255, 255)),
+        (Color::White, (255, 255, 255)),
+    ];
+
+    ANSI.iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .unwrap()
+        .0
+}
+
+/// Squared distance between two RGB colors.
+fn distance((r0, g0, b0): (u8, u8, u8), (r1, g1, b1): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+
+    d(r0, r1) + d(g0, g1) + d(b0, b1)
+}
@@ -0,0 +1,127 @@
+//! Spaceship tracking
+//!
+//! A spaceship is run on its own once to find each of its phases and how far it moves each
+//! period. From then on, where it should be is worked out from the generation, and every time the
+//! view follows it the grid is checked to make sure it's still there, so the view can follow it
+//! until it runs into something.
+use crate::{
+    census,
+    life::Life,
+    pattern::Pattern,
+    period::{Behavior, Period},
+};
+
+/// Follows a spaceship across the grid.
+pub struct Tracker {
+    /// The spaceship's phases over one period, trimmed to their bounding boxes, with where their
+    /// top-left corners are compared to the first phase's.
+    phases: Vec<(Pattern, (isize, isize))>,
+
+    /// Top-left corner of the first phase's bounding box at the generation the period starts.
+    origin: (usize, usize),
+
+    /// How the spaceship moves, since a generation it was in its first phase.
+    period: Period,
+}
+
+impl Tracker {
+    /// Start tracking the spaceship in a region of the grid as `(x, y, width, height)`.
+    pub fn new(life: &Life, region: (usize, usize, usize, usize)) -> crate::Result<Tracker> {
+        let (x, y, width, height) = region;
+        let (bx, by, bw, bh) = life
+            .copy_region(x, y, width, height)
+            .bounding_box()
+            .ok_or("Nothing to track")?;
+
+        let origin = ((x + bx) % life.width(), (y + by) % life.height());
+        let phase = life.copy_region(origin.0, origin.1, bw, bh);
+
+        let spaceship =
+            census::spaceship(&phase, &life.rule()).ok_or("Selection isn't a spaceship")?;
+        let (dx, dy) = spaceship.shift;
+
+        Ok(Tracker {
+            period: Period {
+                behavior: Behavior::Spaceship { dx, dy },
+                period: spaceship.phases.len() as u64,
+                since: life.generation(),
+            },
+            phases: spaceship.phases,
+            origin,
+        })
+    }
+
+    /// Describe the spaceship being tracked, like `period 4, c/4 diagonal`.
+    pub fn description(&self) -> String {
+        let Behavior::Spaceship { dx, dy } = self.period.behavior else {
+            unreachable!("only spaceships are tracked");
+        };
+
+        format!(
+            "period {}, {}",
+            self.period.period,
+            self.period.speed(dx, dy)
+        )
+    }
+
+    /// Work out where the spaceship should be in the current generation.
+    ///
+    /// Returns the phase it should be in and the top-left corner of its bounding box, or `None`
+    /// if the generation went backwards.
+    fn position(&self, life: &Life) -> Option<(&Pattern, (usize, usize))> {
+        let Behavior::Spaceship { dx, dy } = self.period.behavior else {
+            return None;
+        };
+
+        let elapsed = life.generation().checked_sub(self.period.since)?;
+        let periods = (elapsed / self.period.period) as i64;
+        let (phase, (offset_x, offset_y)) = &self.phases[(elapsed % self.period.period) as usize];
+
+        let (width, height) = (life.width() as i64, life.height() as i64);
+        let x = (self.origin.0 as i64 + dx as i64 * periods + *offset_x as i64).rem_euclid(width);
+        let y = (self.origin.1 as i64 + dy as i64 * periods + *offset_y as i64).rem_euclid(height);
+
+        Some((phase, (x as usize, y as usize)))
+    }
+
+    /// Find the top-left corner of the spaceship's bounding box in the current generation,
+    /// without checking that it's there.
+    pub fn corner(&self, life: &Life) -> Option<(usize, usize)> {
+        self.position(life).map(|(_, corner)| corner)
+    }
+
+    /// Carry on tracking after the cells move, like when the grid is resized, with the
+    /// top-left corner of the spaceship's bounding box now at `(x, y)`.
+    pub fn moved_to(&mut self, life: &Life, (x, y): (usize, usize)) {
+        let Some(elapsed) = life.generation().checked_sub(self.period.since) else {
+            return;
+        };
+
+        let phase = elapsed % self.period.period;
+        let (_, (offset_x, offset_y)) = self.phases[phase as usize];
+
+        self.origin = (
+            (x as isize - offset_x).rem_euclid(life.width() as isize) as usize,
+            (y as isize - offset_y).rem_euclid(life.height() as isize) as usize,
+        );
+        self.period.since = life.generation() - phase;
+    }
+
+    /// Find the middle of the spaceship in the current generation.
+    ///
+    /// Returns `None` if it isn't where it should be, because it ran into something, or if the
+    /// generation went backwards.
+    pub fn follow(&self, life: &Life) -> Option<(usize, usize)> {
+        let (phase, (x, y)) = self.position(life)?;
+        let (phase_w, phase_h) = (phase.width(), phase.height());
+
+        if life.copy_region(x, y, phase_w, phase_h) != *phase {
+            return None;
+        }
+
+        Some((
+            (x + phase_w / 2) % life.width(),
+            (y + phase_h / 2) % life.height(),
+        ))
+    }
+}
@@ -1,10 +1,14 @@
 //! Various utility functions
 use crossterm::terminal;
 use ratatui::{
-    layout::{Constraint, Direction, Flex, Layout, Size},
+    layout::{Constraint, Direction, Flex, Layout, Position, Size},
     prelude::{Frame, Rect},
     style::Color,
 };
+use std::{
+    io::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 /// Clamp x, y coordinates to a [`Rect`].
 #[allow(dead_code)]
@@ -78,3 +82,64 @@ pub fn get_terminal_size() -> Size {
     let (cols, rows) = terminal::size().unwrap_or((80, 24));
     Size::new(cols, rows)
 }
+
+/// Get the line of an area a mouse click landed on, counting from the top, if it's inside.
+pub fn clicked_row(area: Rect, column: u16, row: u16) -> Option<usize> {
+    area.contains(Position::new(column, row))
+        .then(|| (row - area.y) as usize)
+}
+
+/// Make a file name that's new every second, like `rustlife-census-1700000000.txt` for a name of
+/// `rustlife-census` and an extension of `txt`.
+pub fn timestamped_file_name(name: &str, extension: &str) -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    format!("{name}-{secs}.{extension}")
+}
+
+/// Draw values as a one-line bar chart, scaled so the largest fills a whole character.
+pub fn sparkline(values: impl Iterator<Item = usize> + Clone) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let max = values.clone().max().unwrap_or(0).max(1);
+
+    values
+        .map(|value| BARS[(value * (BARS.len() - 1)).div_ceil(max)])
+        .collect()
+}
+
+/// Put text on the system clipboard using the OSC 52 terminal escape.
+///
+/// This goes through the terminal, so it works over SSH, but not every terminal supports it.
+pub fn set_clipboard(text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+
+    write!(stdout, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Encode bytes as standard padded base64.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
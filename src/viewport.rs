@@ -0,0 +1,431 @@
+//! Viewport onto the Life universe
+//!
+//! The universe is a torus, so the viewport's top-left corner can be anywhere in it and the view
+//! wraps around the edges. If the view is larger than the universe, the universe is only drawn
+//! once.
+//!
+//! The view can be zoomed out so several cells are packed into each screen character, or zoomed
+//! in so each cell covers a block of characters.
+use ratatui::prelude::Rect;
+
+use crate::life::Life;
+
+/// How cells are packed into screen characters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zoom {
+    /// One cell per character.
+    Normal,
+
+    /// 1x2 cells per character using half blocks.
+    HalfBlock,
+
+    /// 2x2 cells per character using quadrant blocks.
+    Quadrant,
+
+    /// 2x4 cells per character using braille dots.
+    Braille,
+
+    /// NxN cells per character, shaded by how many are alive.
+    Density(usize),
+
+    /// Each cell is drawn as a block of WxH characters.
+    Block(usize, usize),
+}
+
+/// Index of [`Zoom::Normal`] in the zoom levels.
+const ZOOM_NORMAL: usize = 6;
+
+/// Available zoom levels, from farthest out to farthest in.
+const ZOOM_LEVELS: [Zoom; 11] = [
+    Zoom::Density(16),
+    Zoom::Density(8),
+    Zoom::Density(4),
+    Zoom::Braille,
+    Zoom::Quadrant,
+    Zoom::HalfBlock,
+    Zoom::Normal,
+    Zoom::Block(2, 1),
+    Zoom::Block(4, 2),
+    Zoom::Block(6, 3),
+    Zoom::Block(8, 4),
+];
+
+impl Zoom {
+    /// Get the number of cells horizontally and vertically in each character.
+    pub fn cells_per_char(&self) -> (usize, usize) {
+        match self {
+            Zoom::Normal => (1, 1),
+            Zoom::HalfBlock => (1, 2),
+            Zoom::Quadrant => (2, 2),
+            Zoom::Braille => (2, 4),
+            Zoom::Density(n) => (*n, *n),
+            Zoom::Block(..) => (1, 1),
+        }
+    }
+
+    /// Get the number of characters horizontally and vertically in each cell.
+    pub fn chars_per_cell(&self) -> (usize, usize) {
+        match self {
+            Zoom::Block(w, h) => (*w, *h),
+            _ => (1, 1),
+        }
+    }
+
+    /// Get a short description of the zoom level for the status bar.
+    pub fn label(&self) -> String {
+        match self {
+            Zoom::Block(w, h) => format!("{w}x{h}"),
+            _ => {
+                let (cx, cy) = self.cells_per_char();
+                format!("1:{}", cx * cy)
+            }
+        }
+    }
+}
+
+/// A window onto the Life universe.
+pub struct Viewport {
+    /// Universe X coordinate of the top-left cell.
+    pub x: usize,
+
+    /// Universe Y coordinate of the top-left cell.
+    pub y: usize,
+
+    /// Screen area the universe is drawn in, updated on every draw.
+    pub area: Rect,
+
+    /// Index into the zoom levels.
+    zoom_level: usize,
+
+    /// True if gridlines are drawn between cells when zoomed in.
+    pub gridlines: bool,
+}
+
+impl Viewport {
+    /// Create a new Viewport.
+    pub fn new() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            area: Rect::default(),
+            zoom_level: ZOOM_NORMAL,
+            gridlines: false,
+        }
+    }
+
+    /// Get the current zoom.
+    pub fn zoom(&self) -> Zoom {
+        ZOOM_LEVELS[self.zoom_level]
+    }
+
+    /// Get the index of the current zoom into the zoom levels.
+    pub fn zoom_level(&self) -> usize {
+        self.zoom_level
+    }
+
+    /// Set the zoom by its index into the zoom levels, keeping it in range.
+    pub fn set_zoom_level(&mut self, zoom_level: usize) {
+        self.zoom_level = zoom_level.min(ZOOM_LEVELS.len() - 1);
+    }
+
+    /// Zoom in one level. Returns `false` if already all the way in.
+    pub fn zoom_in(&mut self) -> bool {
+        if self.zoom_level + 1 < ZOOM_LEVELS.len() {
+            self.zoom_level += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Zoom out one level. Returns `false` if already all the way out.
+    pub fn zoom_out(&mut self) -> bool {
+        if self.zoom_level > 0 {
+            self.zoom_level -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the number of cells that fit in the screen area, ignoring the universe size.
+    pub fn area_cells(&self) -> (usize, usize) {
+        let (cx, cy) = self.zoom().cells_per_char();
+        let (chw, chh) = self.zoom().chars_per_cell();
+
+        (
+            self.area.width as usize / chw * cx,
+            self.area.height as usize / chh * cy,
+        )
+    }
+
+    /// Get the number of cells visible horizontally and vertically.
+    pub fn visible_cells(&self, life: &Life) -> (usize, usize) {
+        let (area_w, area_h) = self.area_cells();
+
+        (area_w.min(life.width()), area_h.min(life.height()))
+    }
+
+    /// Get the offset of a universe coordinate from the top-left of the view.
+    fn offset(&self, x: usize, y: usize, life: &Life) -> (usize, usize) {
+        (
+            (x + life.width() - self.x) % life.width().max(1),
+            (y + life.height() - self.y) % life.height().max(1),
+        )
+    }
+
+    /// Get the screen position of a cell, or `None` if it's not in view.
+    ///
+    /// When zoomed out, this is the character the cell is packed into. When zoomed in, it's the
+    /// top-left character of the cell.
+    pub fn cell_to_screen(&self, x: usize, y: usize, life: &Life) -> Option<(u16, u16)> {
+        let (vis_w, vis_h) = self.visible_cells(life);
+        let (dx, dy) = self.offset(x, y, life);
+        let (cx, cy) = self.zoom().cells_per_char();
+        let (chw, chh) = self.zoom().chars_per_cell();
+
+        if dx < vis_w && dy < vis_h {
+            Some((
+                self.area.x + (dx / cx * chw) as u16,
+                self.area.y + (dy / cy * chh) as u16,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Get the offset from the top-left of the view of the cell under a screen position, or
+    /// `None` if there isn't one.
+    ///
+    /// When zoomed out, this is the top-left cell packed into the character.
+    pub fn screen_to_offset(&self, sx: u16, sy: u16, life: &Life) -> Option<(usize, usize)> {
+        let (vis_w, vis_h) = self.visible_cells(life);
+        let (cx, cy) = self.zoom().cells_per_char();
+        let (chw, chh) = self.zoom().chars_per_cell();
+
+        if sx < self.area.x || sy < self.area.y {
+            return None;
+        }
+
+        let dx = (sx - self.area.x) as usize / chw * cx;
+        let dy = (sy - self.area.y) as usize / chh * cy;
+
+        (dx < vis_w && dy < vis_h).then_some((dx, dy))
+    }
+
+    /// Get the universe coordinate under a screen position, or `None` if there isn't one.
+    ///
+    /// When zoomed out, this is the top-left cell packed into the character.
+    pub fn screen_to_cell(&self, sx: u16, sy: u16, life: &Life) -> Option<(usize, usize)> {
+        self.screen_to_offset(sx, sy, life)
+            .map(|(dx, dy)| ((self.x + dx) % life.width(), (self.y + dy) % life.height()))
+    }
+
+    /// Move the view so a cell is under a screen position.
+    pub fn anchor(&mut self, x: usize, y: usize, sx: u16, sy: u16, life: &Life) {
+        let (cx, cy) = self.zoom().cells_per_char();
+        let (chw, chh) = self.zoom().chars_per_cell();
+
+        let dx = sx.saturating_sub(self.area.x) as usize / chw * cx;
+        let dy = sy.saturating_sub(self.area.y) as usize / chh * cy;
+
+        self.x = x;
+        self.y = y;
+        self.pan(-(dx as isize), -(dy as isize), life);
+    }
+
+    /// Pan the view by a number of cells, wrapping around the universe.
+    pub fn pan(&mut self, dx: isize, dy: isize, life: &Life) {
+        if life.width() == 0 || life.height() == 0 {
+            return;
+        }
+
+        self.x = (self.x as isize + dx).rem_euclid(life.width() as isize) as usize;
+        self.y = (self.y as isize + dy).rem_euclid(life.height() as isize) as usize;
+    }
+
+    /// Scroll the minimum amount needed to bring a cell into view.
+    pub fn scroll_to(&mut self, x: usize, y: usize, life: &Life) {
+        let (vis_w, vis_h) = self.visible_cells(life);
+        let (dx, dy) = self.offset(x, y, life);
+
+        if vis_w > 0 && dx >= vis_w {
+            let right = dx - (vis_w - 1);
+            let left = life.width() - dx;
+
+            if right <= left {
+                self.pan(right as isize, 0, life);
+            } else {
+                self.pan(-(left as isize), 0, life);
+            }
+        }
+
+        if vis_h > 0 && dy >= vis_h {
+            let down = dy - (vis_h - 1);
+            let up = life.height() - dy;
+
+            if down <= up {
+                self.pan(0, down as isize, life);
+            } else {
+                self.pan(0, -(up as isize), life);
+            }
+        }
+    }
+
+    /// Move the view so a cell is in the center.
+    pub fn center_on(&mut self, x: usize, y: usize, life: &Life) {
+        let (vis_w, vis_h) = self.visible_cells(life);
+
+        self.x = x;
+        self.y = y;
+        self.pan(-(vis_w as isize / 2), -(vis_h as isize / 2), life);
+    }
+
+    /// Zoom and move the view so the pattern's bounding box is centered.
+    ///
+    /// This picks the closest zoom level that shows the whole pattern. Returns `false` if there is
+    /// no pattern.
+    pub fn fit(&mut self, life: &Life) -> bool {
+        let Some((x, y, width, height)) = life.bounding_box() else {
+            return false;
+        };
+
+        self.zoom_level = ZOOM_LEVELS.len() - 1;
+
+        while self.zoom_level > 0 {
+            let (area_w, area_h) = self.area_cells();
+
+            if width <= area_w && height <= area_h {
+                break;
+            }
+
+            self.zoom_level -= 1;
+        }
+
+        self.center_on(x + width / 2, y + height / 2, life);
+
+        true
+    }
+}
+
+impl Default for Viewport {
+    /// Create a default Viewport.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::LifeCell;
+
+    /// Make an empty universe and a view of it in a screen area.
+    fn setup(width: usize, height: usize, area: Rect) -> (Life, Viewport) {
+        let mut life = Life::new();
+        life.init(width, height);
+
+        let mut viewport = Viewport::new();
+        viewport.area = area;
+
+        (life, viewport)
+    }
+
+    #[test]
+    fn screen_and_cells_line_up_at_every_zoom() {
+        let (life, mut viewport) = setup(100, 60, Rect::new(1, 1, 40, 20));
+        viewport.x = 95;
+        viewport.y = 58;
+
+        for level in 0..ZOOM_LEVELS.len() {
+            viewport.set_zoom_level(level);
+
+            let (cx, cy) = viewport.zoom().cells_per_char();
+            let (x, y) = ((95 + cx) % 100, (58 + cy) % 60);
+            let (sx, sy) = viewport.cell_to_screen(x, y, &life).unwrap();
+
+            assert_eq!(viewport.screen_to_cell(sx, sy, &life), Some((x, y)));
+        }
+    }
+
+    #[test]
+    fn view_wraps_around_the_universe() {
+        let (life, mut viewport) = setup(100, 60, Rect::new(0, 0, 20, 10));
+        viewport.x = 90;
+        viewport.y = 55;
+
+        assert_eq!(viewport.cell_to_screen(90, 55, &life), Some((0, 0)));
+        assert_eq!(viewport.cell_to_screen(2, 1, &life), Some((12, 6)));
+        assert_eq!(viewport.cell_to_screen(50, 30, &life), None);
+
+        viewport.pan(15, -60, &life);
+        assert_eq!((viewport.x, viewport.y), (5, 55));
+    }
+
+    #[test]
+    fn universe_smaller_than_the_view_is_drawn_once() {
+        let (life, viewport) = setup(10, 5, Rect::new(0, 0, 40, 20));
+
+        assert_eq!(viewport.visible_cells(&life), (10, 5));
+        assert_eq!(viewport.screen_to_cell(9, 4, &life), Some((9, 4)));
+        assert_eq!(viewport.screen_to_cell(10, 0, &life), None);
+        assert_eq!(viewport.screen_to_cell(0, 5, &life), None);
+    }
+
+    #[test]
+    fn scrolls_the_short_way() {
+        let (life, mut viewport) = setup(100, 60, Rect::new(0, 0, 20, 10));
+
+        viewport.scroll_to(5, 5, &life);
+        assert_eq!((viewport.x, viewport.y), (0, 0));
+
+        viewport.scroll_to(25, 12, &life);
+        assert_eq!((viewport.x, viewport.y), (6, 3));
+
+        viewport.scroll_to(98, 59, &life);
+        assert_eq!((viewport.x, viewport.y), (98, 59));
+    }
+
+    #[test]
+    fn centers_on_a_cell() {
+        let (life, mut viewport) = setup(100, 60, Rect::new(0, 0, 20, 10));
+
+        viewport.center_on(5, 2, &life);
+
+        assert_eq!((viewport.x, viewport.y), (95, 57));
+        assert_eq!(viewport.cell_to_screen(5, 2, &life), Some((10, 5)));
+    }
+
+    #[test]
+    fn zoom_stops_at_the_ends() {
+        let mut viewport = Viewport::new();
+
+        while viewport.zoom_out() {}
+        assert_eq!(viewport.zoom(), Zoom::Density(16));
+        assert_eq!(viewport.zoom().label(), "1:256");
+
+        while viewport.zoom_in() {}
+        assert_eq!(viewport.zoom(), Zoom::Block(8, 4));
+        assert_eq!(viewport.zoom().label(), "8x4");
+
+        viewport.set_zoom_level(1000);
+        assert_eq!(viewport.zoom_level(), ZOOM_LEVELS.len() - 1);
+    }
+
+    #[test]
+    fn fit_picks_the_closest_zoom_that_shows_everything() {
+        let (mut life, mut viewport) = setup(200, 100, Rect::new(0, 0, 40, 20));
+        assert!(!viewport.fit(&life));
+
+        life.set_cell(10, 10, LifeCell::Alive);
+        life.set_cell(13, 11, LifeCell::Alive);
+        assert!(viewport.fit(&life));
+        assert_eq!(viewport.zoom(), Zoom::Block(8, 4));
+
+        life.set_cell(70, 40, LifeCell::Alive);
+        assert!(viewport.fit(&life));
+        assert_eq!(viewport.zoom(), Zoom::Quadrant);
+        assert!(viewport.cell_to_screen(10, 10, &life).is_some());
+        assert!(viewport.cell_to_screen(70, 40, &life).is_some());
+    }
+}
@@ -1,12 +1,16 @@
 //! This widget draws the help window.
 //!
+//! The help is made from the keymap, so it lists the keys that are actually bound. It's split
+//! into two columns when there's room, and scrolls when there isn't.
+//!
 //! Example:
 //!
 //! ```
-//! let help = HelpWidget::new();
+//! let help = HelpWidget::new(&theme, &keymap).scroll(scroll);
 //! frame.render_widget(help, inner[0]);
 //! ```
 use ratatui::{
+    layout::Size,
     prelude::{Buffer, Rect, Stylize},
     style::Style,
     symbols::border,
@@ -14,88 +18,208 @@ use ratatui::{
     widgets::{Block, Clear, Padding, Paragraph, Widget},
 };
 
-use crate::util;
+use crate::{
+    keymap::{Action, Keymap},
+    theme::Theme,
+};
+
+/// Space between the two columns.
+const COLUMN_GAP: u16 = 3;
 
 /// A HelpWidget.
-pub struct HelpWidget {}
+pub struct HelpWidget<'a> {
+    /// Colors to draw with.
+    theme: &'a Theme,
+
+    /// Keys to list.
+    keymap: &'a Keymap,
 
-impl HelpWidget {
+    /// Lines scrolled off the top.
+    scroll: u16,
+}
+
+impl<'a> HelpWidget<'a> {
     /// Construct a new HelpWidget.
-    pub fn new() -> Self {
-        HelpWidget {}
+    pub fn new(theme: &'a Theme, keymap: &'a Keymap) -> Self {
+        HelpWidget {
+            theme,
+            keymap,
+            scroll: 0,
+        }
+    }
+
+    /// Set the number of lines scrolled off the top.
+    pub fn scroll(mut self, scroll: u16) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    /// Make the block around the help.
+    fn block(&self, scrollable: bool) -> Block<'static> {
+        Block::bordered()
+            .title(Line::from(" Help ".bold()))
+            .title_bottom(
+                Line::from(if scrollable {
+                    " ↑↓→Scroll | Other keys→Close "
+                } else {
+                    " Press any key "
+                })
+                .centered(),
+            )
+            .padding(Padding::new(2, 2, 1, 1))
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(self.theme.dialog_border))
+    }
+
+    /// Get the help as sections of lines, each starting with its heading.
+    fn sections(&self) -> Vec<Vec<Line<'static>>> {
+        let s = Style::new().fg(self.theme.dialog_key);
+        let keys: Vec<String> = Action::ALL
+            .iter()
+            .map(|action| {
+                self.keymap
+                    .keys(*action)
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect();
+
+        let keys_width = keys.iter().map(|k| k.chars().count()).max().unwrap_or(0);
+
+        let mut sections: Vec<Vec<Line>> = Vec::new();
+        let mut heading = "";
+
+        for (action, keys) in Action::ALL.iter().zip(keys) {
+            if action.section() != heading {
+                heading = action.section();
+                sections.push(vec![Line::from(heading.bold())]);
+            }
+
+            // Unbound actions are left out
+            if keys.is_empty() {
+                continue;
+            }
+
+            sections.last_mut().unwrap().push(Line::from(vec![
+                Span::styled(format!("{keys:keys_width$}"), s),
+                Span::raw(format!("  {}", action.description())),
+            ]));
+        }
+
+        // Counts aren't bound to keys, so describe them in terms of the keys that use them
+        let first_key = |action| {
+            self.keymap
+                .keys(action)
+                .first()
+                .map_or(String::from("?"), |key| key.to_string())
+        };
+
+        sections.push(vec![
+            Line::from("Counts".bold()),
+            Line::from(vec![
+                Span::raw("A number then "),
+                Span::styled(first_key(Action::MoveLeft), s),
+                Span::raw(" or "),
+                Span::styled(first_key(Action::MoveDown), s),
+                Span::raw(" draws"),
+            ]),
+            Line::from("a horizontal or vertical line."),
+        ]);
+
+        sections
+    }
+
+    /// Lay the help out in columns, with a blank line between sections.
+    ///
+    /// Sections aren't split between columns.
+    fn columns(&self, columns: usize) -> Vec<Vec<Line<'static>>> {
+        let sections = self.sections();
+        let total: usize = sections.iter().map(|section| section.len() + 1).sum();
+        let mut result: Vec<Vec<Line>> = vec![Vec::new()];
+
+        for section in sections {
+            let column = result.last().unwrap();
+
+            // Start the next column once this one is over its share
+            if result.len() < columns && column.len() + section.len() / 2 > total / columns {
+                result.push(Vec::new());
+            }
+
+            let column = result.last_mut().unwrap();
+
+            if !column.is_empty() {
+                column.push(Line::default());
+            }
+
+            column.extend(section);
+        }
+
+        result
+    }
+
+    /// Get the width of the widest line in a column.
+    fn column_width(column: &[Line]) -> u16 {
+        column.iter().map(Line::width).max().unwrap_or(0) as u16
+    }
+
+    /// Get the number of columns that fit in a width inside the border.
+    fn fit_columns(&self, inner_width: u16) -> usize {
+        let two = self.columns(2);
+        let two_width = two.iter().map(|c| Self::column_width(c)).sum::<u16>() + COLUMN_GAP;
+
+        if inner_width >= two_width { 2 } else { 1 }
+    }
+
+    /// Get the best size for the whole window, no bigger than `max`.
+    pub fn size(&self, max: Size) -> Size {
+        let columns = self.columns(self.fit_columns(max.width.saturating_sub(6)));
+        let width = columns.iter().map(|c| Self::column_width(c)).sum::<u16>()
+            + COLUMN_GAP * (columns.len() as u16 - 1)
+            + 6;
+        let height = columns.iter().map(Vec::len).max().unwrap_or(0) as u16 + 4;
+
+        Size::new(width.min(max.width), height.min(max.height))
+    }
+
+    /// Get the most lines that can be scrolled off the top in an area.
+    pub fn max_scroll(&self, area: Rect) -> u16 {
+        let inner = self.block(false).inner(area);
+        let columns = self.columns(self.fit_columns(inner.width));
+        let height = columns.iter().map(Vec::len).max().unwrap_or(0) as u16;
+
+        height.saturating_sub(inner.height)
     }
 }
 
-impl Widget for HelpWidget {
+impl Widget for HelpWidget<'_> {
     /// Render this HelpWidget.
     fn render(self, area: Rect, buf: &mut Buffer) {
         Widget::render(&self, area, buf);
     }
 }
 
-impl Widget for &HelpWidget {
+impl Widget for &HelpWidget<'_> {
     /// Render this HelpWidget.
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let block = Block::bordered()
-            .title(Line::from(" Help ".bold()))
-            .title_bottom(Line::from(" Press any key ").centered())
-            .padding(Padding::new(2, 2, 1, 1))
-            .border_set(border::THICK);
+        let block = self.block(self.max_scroll(area) > 0);
+        let inner = block.inner(area);
 
-        let s = Style::new().fg(util::rgb6_to_indexed(3, 3, 5));
+        Widget::render(Clear, area, buf);
+        block.render(area, buf);
 
-        let text = vec![
-            Line::styled("y k u", s),
-            " \\|/".into(),
-            Line::from(vec![
-                Span::styled("h", s),
-                Span::raw("-+-"),
-                Span::styled("l", s),
-                Span::raw("  Cursor movement"),
-            ]),
-            " /|\\".into(),
-            Line::from(vec![
-                Span::styled("b j n", s),
-                Span::raw("  (or arrow keys)"),
-            ]),
-            "".into(),
-            Line::from(vec![
-                Span::styled("t", s),
-                Span::raw(": Toggle cell       "),
-                Span::styled("s", s),
-                Span::raw(": Step"),
-            ]),
-            Line::from(vec![
-                Span::styled("r", s),
-                Span::raw(": Run start/stop    "),
-                Span::styled("c", s),
-                Span::raw(": Clear screen"),
-            ]),
-            Line::from(vec![
-                Span::styled("R", s),
-                Span::raw(": Randomize         "),
-                Span::styled("?", s),
-                Span::raw(": Help"),
-            ]),
-            Line::from(vec![
-                Span::styled("a", s),
-                Span::raw(": About             "),
-                Span::styled("q", s),
-                Span::raw(": Help"),
-            ]),
-            "".into(),
-            Line::from(vec![
-                Span::raw("Numeric count followed by \""),
-                Span::styled("h", s),
-                Span::raw("\" draws a"),
-            ]),
-            "horizontal line.".into(),
-        ];
+        let mut x = inner.x;
 
-        let paragraph = Paragraph::new(text).block(block);
+        for column in self.columns(self.fit_columns(inner.width)) {
+            let width = HelpWidget::column_width(&column).min(inner.right().saturating_sub(x));
+            let column_area = Rect::new(x, inner.y, width, inner.height);
 
-        Widget::render(Clear, area, buf);
+            Paragraph::new(column)
+                .scroll((self.scroll, 0))
+                .render(column_area, buf);
 
-        paragraph.render(area, buf);
+            x = (x + width + COLUMN_GAP).min(inner.right());
+        }
     }
 }
@@ -9,8 +9,8 @@
 //!    .title(title.centered())
 //!    .border_set(border::THICK);
 //!
-//!// `life` is a `Life` object
-//!let life_widget = LifeWidget::new(&life).block(block);
+//!// `life` is a `Life` object, `viewport` is a `Viewport`
+//!let life_widget = LifeWidget::new(&life).block(block).viewport(&viewport);
 //!
 //!let inner = life_widget.inner(frame.area());
 //!
@@ -18,11 +18,63 @@
 //!```
 //![`Life`]: crate::life::Life
 use ratatui::{
-    prelude::{BlockExt, Buffer, Rect},
-    widgets::{Block, Widget},
+    prelude::{BlockExt, Buffer, Rect, Stylize},
+    style::{Modifier, Style},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Padding, Paragraph, Widget},
 };
 
-use crate::{life, util};
+use crate::{
+    life,
+    pattern::{PasteMode, Pattern},
+    theme::{ColorDepth, TRAIL_LENGTH, Theme},
+    viewport::{Viewport, Zoom},
+};
+
+/// Quadrant block characters indexed by top-left, top-right, bottom-left, bottom-right bits.
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// Cell offsets for each bit of a braille character, starting at U+2800.
+const BRAILLE_DOTS: [(usize, usize); 8] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (0, 3),
+    (1, 3),
+];
+
+/// Shades for the density zoom levels, from sparsest to fullest.
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+/// How to color the cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellColoring {
+    /// All live cells are the same color.
+    Plain,
+
+    /// Live cells are colored by age.
+    Age,
+
+    /// Live cells are colored by age, and recently-dead cells leave fading trails.
+    AgeTrails,
+}
+
+impl CellColoring {
+    /// Get the next coloring in the cycle.
+    pub fn next(self) -> Self {
+        match self {
+            CellColoring::Plain => CellColoring::Age,
+            CellColoring::Age => CellColoring::AgeTrails,
+            CellColoring::AgeTrails => CellColoring::Plain,
+        }
+    }
+}
 
 /// A LifeWidget structure.
 pub struct LifeWidget<'a> {
@@ -31,12 +83,65 @@ pub struct LifeWidget<'a> {
 
     /// Reference to the life data structure.
     life: &'a life::Life,
+
+    /// Optional viewport; without one the top-left cell is drawn at the top-left.
+    viewport: Option<&'a Viewport>,
+
+    /// How to color the cells.
+    coloring: CellColoring,
+
+    /// Colors and glyphs; the default theme is used if this is `None`.
+    theme: Option<&'a Theme>,
+
+    /// Cell to highlight as the cursor, if the theme has a cursor color.
+    cursor: Option<(usize, usize)>,
+
+    /// Highlighted rectangle as `(x, y, width, height)`.
+    selection: Option<(usize, usize, usize, usize)>,
+
+    /// Pattern floating over the grid.
+    preview: Option<Preview<'a>>,
+
+    /// True to outline the pattern's bounding box and show what's happening at the cursor.
+    info: bool,
+
+    /// How each cell will change next generation, to show births and deaths ahead of time.
+    changes: Option<&'a Vec<Vec<life::Change>>>,
+}
+
+/// A pattern floating over the grid, shown the way it would look if it were pasted.
+pub struct Preview<'a> {
+    /// The pattern.
+    pub pattern: &'a Pattern,
+
+    /// Grid X coordinate of the top-left of the pattern.
+    pub x: usize,
+
+    /// Grid Y coordinate of the top-left of the pattern.
+    pub y: usize,
+
+    /// How the pattern is combined with the grid.
+    pub mode: PasteMode,
+
+    /// True to only highlight the pattern's live cells instead of its whole rectangle.
+    pub cells_only: bool,
 }
 
 impl<'a> LifeWidget<'a> {
     /// Create a new LifeWidget.
     pub fn new(life: &'a life::Life) -> Self {
-        Self { block: None, life }
+        Self {
+            block: None,
+            life,
+            viewport: None,
+            coloring: CellColoring::Plain,
+            theme: None,
+            cursor: None,
+            selection: None,
+            preview: None,
+            info: false,
+            changes: None,
+        }
     }
 
     /// Add a Block widget to this LifeWidget.
@@ -46,6 +151,63 @@ impl<'a> LifeWidget<'a> {
         self
     }
 
+    /// Set the Viewport to draw through.
+    pub fn viewport(mut self, viewport: &'a Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Set how to color the cells.
+    ///
+    /// Age coloring only works if the [`Life`] object is tracking ages.
+    ///
+    /// [`Life`]: crate::life::Life
+    pub fn coloring(mut self, coloring: CellColoring) -> Self {
+        self.coloring = coloring;
+        self
+    }
+
+    /// Set the theme.
+    pub fn theme(mut self, theme: &'a Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Set the cursor position to highlight.
+    pub fn cursor(mut self, x: usize, y: usize) -> Self {
+        self.cursor = Some((x, y));
+        self
+    }
+
+    /// Set a rectangle to highlight as `(x, y, width, height)`.
+    pub fn selection(mut self, selection: Option<(usize, usize, usize, usize)>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Set a pattern to float over the grid.
+    pub fn preview(mut self, preview: Option<Preview<'a>>) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    /// Turn the info overlay on or off.
+    ///
+    /// It needs a viewport, and the cursor for the panel about the cell under it.
+    pub fn info(mut self, info: bool) -> Self {
+        self.info = info;
+        self
+    }
+
+    /// Set how each cell will change next generation, from [`Life::next_changes`], to dim the
+    /// cells that will die and show the ones that will be born.
+    ///
+    /// [`Life::next_changes`]: crate::life::Life::next_changes
+    pub fn changes(mut self, changes: Option<&'a Vec<Vec<life::Change>>>) -> Self {
+        self.changes = changes;
+        self
+    }
+
     /// Get the inner dimensions of the LifeWidget.
     #[allow(dead_code)]
     pub fn inner(&self, area: Rect) -> Rect {
@@ -67,7 +229,14 @@ impl Widget for LifeWidget<'_> {
 impl Widget for &LifeWidget<'_> {
     /// Render this borrowed LifeWidget.
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let alive_color = util::rgb6_to_indexed(3, 5, 3);
+        let default_theme;
+        let theme = match self.theme {
+            Some(theme) => theme,
+            None => {
+                default_theme = Theme::default();
+                &default_theme
+            }
+        };
 
         let area = area.intersection(buf.area);
         if let Some(block) = self.block.as_ref() {
@@ -77,27 +246,425 @@ impl Widget for &LifeWidget<'_> {
         let inner = self.block.inner_if_some(area);
 
         let cells = self.life.get_cells();
+        let width = self.life.width();
+        let height = self.life.height();
+
+        let (view_x, view_y, zoom) = match self.viewport {
+            Some(viewport) => (viewport.x, viewport.y, viewport.zoom()),
+            None => (0, 0, Zoom::Normal),
+        };
+
+        let (cx, cy) = zoom.cells_per_char();
+        let (chw, chh) = zoom.chars_per_cell();
+
+        // If the universe is smaller than the view, only draw it once
+        let vis_w = (inner.width as usize / chw * cx).min(width);
+        let vis_h = (inner.height as usize / chh * cy).min(height);
+
+        let ages = match self.coloring {
+            CellColoring::Plain => None,
+            _ => self.life.get_ages(),
+        };
+
+        let trails = self.coloring == CellColoring::AgeTrails;
+
+        // Whether a cell is inside a rectangle that might wrap around the edges
+        let in_rect = |x: usize, y: usize, (rx, ry, rw, rh): (usize, usize, usize, usize)| {
+            (x + width - rx % width) % width < rw && (y + height - ry % height) % height < rh
+        };
+
+        // Get the cell at an offset from the top-left of the view, along with its age and whether
+        // it's highlighted
+        let cell = |dx: usize, dy: usize| {
+            if dx >= vis_w || dy >= vis_h {
+                return None;
+            }
+
+            let x = (view_x + dx) % width;
+            let y = (view_y + dy) % height;
+            let age = ages.map_or(life::AGE_UNKNOWN, |ages| ages[y][x]);
+            let selected = self.selection.is_some_and(|rect| in_rect(x, y, rect));
+
+            if let Some(preview) = &self.preview {
+                let rect = (
+                    preview.x,
+                    preview.y,
+                    preview.pattern.width(),
+                    preview.pattern.height(),
+                );
+
+                if in_rect(x, y, rect) {
+                    let px = (x + width - preview.x % width) % width;
+                    let py = (y + height - preview.y % height) % height;
+                    let pattern_state = preview.pattern.get_cell(px, py);
+
+                    if !preview.cells_only || pattern_state == life::LifeCell::Alive {
+                        let state = preview.mode.combine(cells[y][x], pattern_state);
+
+                        return Some((state, life::AGE_UNKNOWN, true));
+                    }
+                }
+            }
+
+            Some((cells[y][x], age, selected))
+        };
+
+        let alive =
+            |dx: usize, dy: usize| matches!(cell(dx, dy), Some((life::LifeCell::Alive, _, _)));
+
+        // How the cell at an offset from the top-left of the view will change next generation
+        let change = |dx: usize, dy: usize| match self.changes {
+            Some(changes) if dx < vis_w && dy < vis_h => {
+                changes[(view_y + dy) % height][(view_x + dx) % width]
+            }
+            _ => life::Change::Same,
+        };
+
+        let born = |dx: usize, dy: usize| change(dx, dy) == life::Change::Birth;
+
+        let ghost = |dx: usize, dy: usize| matches!(cell(dx, dy), Some((life::LifeCell::Dead, age, _)) if age < TRAIL_LENGTH);
 
-        for (y, row) in cells.iter().enumerate() {
-            // There's a race where the window and widget might have resized before the Life
-            // object resized. We break off if we exceed the bounds.
-            if y as u16 >= inner.height {
-                break;
+        let highlight_style = if theme.depth == ColorDepth::None {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new().bg(theme.selection)
+        };
+
+        let gridlines = self.viewport.is_some_and(|v| v.gridlines);
+        let dead_glyph = match zoom {
+            Zoom::Normal | Zoom::Block(..) => Some(theme.dead_glyph).filter(|g| *g != ' '),
+            _ => None,
+        };
+
+        for ty in 0..vis_h.div_ceil(cy) {
+            for tx in 0..vis_w.div_ceil(cx) {
+                let dx = tx * cx;
+                let dy = ty * cy;
+
+                // Find the youngest live and recently-dead cells in this tile, whether any of it
+                // is highlighted, and whether its live cells all die or any dead ones are born
+                let mut live_age = None;
+                let mut ghost_age = None;
+                let mut highlight = false;
+                let mut dying = true;
+                let mut births = false;
+
+                for y in 0..cy {
+                    for x in 0..cx {
+                        let Some((state, age, highlighted)) = cell(dx + x, dy + y) else {
+                            continue;
+                        };
+
+                        highlight |= highlighted;
+
+                        match change(dx + x, dy + y) {
+                            life::Change::Birth => births = true,
+                            life::Change::Same if state == life::LifeCell::Alive => dying = false,
+                            _ => (),
+                        }
+
+                        match state {
+                            life::LifeCell::Alive => {
+                                live_age = Some(live_age.map_or(age, |a: u16| a.min(age)));
+                            }
+                            life::LifeCell::Dead if age < TRAIL_LENGTH => {
+                                ghost_age = Some(ghost_age.map_or(age, |a: u16| a.min(age)));
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+
+                let dying = dying && live_age.is_some();
+
+                let (glyph, color) = if let Some(age) = live_age {
+                    let color = if dying {
+                        theme.death
+                    } else if ages.is_some() {
+                        theme.age_color(age)
+                    } else {
+                        theme.alive
+                    };
+
+                    let glyph = tile_glyph(zoom, theme.alive_glyph, |x, y| alive(dx + x, dy + y));
+
+                    (glyph, color)
+                } else if births {
+                    let glyph = match zoom {
+                        Zoom::Normal | Zoom::Block(..) => Some('░'),
+                        _ => tile_glyph(zoom, theme.alive_glyph, |x, y| born(dx + x, dy + y)),
+                    };
+
+                    (glyph, theme.birth)
+                } else if let Some(age) = ghost_age
+                    && trails
+                {
+                    // Single cells get a lighter glyph so trails are visible without color
+                    let glyph = match zoom {
+                        Zoom::Normal | Zoom::Block(..) => Some('░'),
+                        _ => tile_glyph(zoom, theme.alive_glyph, |x, y| ghost(dx + x, dy + y)),
+                    };
+
+                    (glyph, theme.ghost_color(age))
+                } else {
+                    (dead_glyph, theme.dead)
+                };
+
+                for y in 0..chh {
+                    for x in 0..chw {
+                        // Gridlines take the right and bottom edge of each cell
+                        let grid_x = gridlines && chw > 1 && x == chw - 1;
+                        let grid_y = gridlines && chh > 1 && y == chh - 1;
+
+                        let glyph = match (grid_x, grid_y, glyph) {
+                            (true, true, _) => Some(('┼', theme.grid)),
+                            (true, false, _) => Some(('│', theme.grid)),
+                            (false, true, _) => Some(('─', theme.grid)),
+                            (false, false, Some(glyph)) => Some((glyph, color)),
+                            _ => None,
+                        };
+
+                        let buf_cell = &mut buf[(
+                            inner.x + (tx * chw + x) as u16,
+                            inner.y + (ty * chh + y) as u16,
+                        )];
+
+                        if let Some((glyph, color)) = glyph {
+                            buf_cell.set_char(glyph).set_fg(color);
+                        }
+
+                        // Without color, dying cells can only be told apart by being dim
+                        if dying && theme.depth == ColorDepth::None && !grid_x && !grid_y {
+                            buf_cell.set_style(Style::new().add_modifier(Modifier::DIM));
+                        }
+
+                        if highlight {
+                            buf_cell.set_style(highlight_style);
+                        }
+                    }
+                }
             }
-            for (x, cell) in row.iter().enumerate() {
-                if x as u16 >= inner.width {
-                    break;
+        }
+
+        if let (Some(cursor_color), Some((x, y)), Some(viewport)) =
+            (theme.cursor, self.cursor, self.viewport)
+            && let Some((sx, sy)) = viewport.cell_to_screen(x, y, self.life)
+        {
+            for y in sy..(sy + chh as u16).min(inner.bottom()) {
+                for x in sx..(sx + chw as u16).min(inner.right()) {
+                    buf[(x, y)].set_bg(cursor_color);
                 }
-                match cell {
-                    life::LifeCell::Alive => {
-                        //buf[(x as u16, y as u16)].set_symbol("▒");
-                        buf[(x as u16 + inner.x, y as u16 + inner.y)]
-                            .set_symbol("▓")
-                            .set_fg(alive_color);
+            }
+        }
+
+        if self.info
+            && let Some(viewport) = self.viewport
+        {
+            self.render_bounding_box(viewport, theme, inner, buf);
+            self.render_info_panel(theme, inner, buf);
+        }
+    }
+}
+
+impl LifeWidget<'_> {
+    /// Outline the bounding box of the live cells, in the dead cells just outside it.
+    fn render_bounding_box(
+        &self,
+        viewport: &Viewport,
+        theme: &Theme,
+        inner: Rect,
+        buf: &mut Buffer,
+    ) {
+        let Some((x, y, width, height)) = self.life.bounding_box() else {
+            return;
+        };
+
+        let (chw, chh) = viewport.zoom().chars_per_cell();
+        let (grid_w, grid_h) = (self.life.width(), self.life.height());
+        let gridlines_x = viewport.gridlines && chw > 1;
+        let gridlines_y = viewport.gridlines && chh > 1;
+
+        // Cells within a tile the outline can draw over
+        let drawable = |symbol: &str| {
+            symbol == " " || symbol == theme.dead_glyph.to_string() || "─│┼".contains(symbol)
+        };
+
+        // Sides are -1 for the left or top, 1 for the right or bottom, and 0 along the box
+        for side_y in -1..=1isize {
+            for side_x in -1..=1isize {
+                let glyph = match (side_x, side_y) {
+                    (-1, -1) => '┌',
+                    (1, -1) => '┐',
+                    (-1, 1) => '└',
+                    (1, 1) => '┘',
+                    (0, 0) => continue,
+                    (0, _) => '─',
+                    (_, 0) => '│',
+                    _ => unreachable!(),
+                };
+
+                // When zoomed in, draw on the side of each cell next to the box, or on the box's
+                // own gridlines at the right and bottom since that's where gridlines go
+                let (xs, columns) = match side_x {
+                    -1 => ((x + grid_w - 1)..(x + grid_w), chw - 1..chw),
+                    1 if gridlines_x => ((x + width - 1)..(x + width), chw - 1..chw),
+                    1 => ((x + width)..(x + width + 1), 0..1),
+                    _ => (x..(x + width), 0..chw),
+                };
+
+                let (ys, rows) = match side_y {
+                    -1 => ((y + grid_h - 1)..(y + grid_h), chh - 1..chh),
+                    1 if gridlines_y => ((y + height - 1)..(y + height), chh - 1..chh),
+                    1 => ((y + height)..(y + height + 1), 0..1),
+                    _ => (y..(y + height), 0..chh),
+                };
+
+                for cy in ys {
+                    for cx in xs.clone() {
+                        let Some((sx, sy)) =
+                            viewport.cell_to_screen(cx % grid_w, cy % grid_h, self.life)
+                        else {
+                            continue;
+                        };
+
+                        for row in rows.clone() {
+                            for column in columns.clone() {
+                                let (sx, sy) = (sx + column as u16, sy + row as u16);
+
+                                if sx < inner.right()
+                                    && sy < inner.bottom()
+                                    && drawable(buf[(sx, sy)].symbol())
+                                {
+                                    buf[(sx, sy)].set_char(glyph).set_fg(theme.dialog_key);
+                                }
+                            }
+                        }
                     }
-                    life::LifeCell::Dead => (),
                 }
             }
         }
     }
+
+    /// Show the population, bounding box, and what's happening to the cell under the cursor in
+    /// the top-right corner.
+    fn render_info_panel(&self, theme: &Theme, inner: Rect, buf: &mut Buffer) {
+        let life = self.life;
+        let mut rows = vec![("Population", life.population().to_string())];
+
+        rows.push((
+            "Box",
+            match life.bounding_box() {
+                Some((x, y, width, height)) => format!("{width}x{height} at {x},{y}"),
+                None => "empty".to_string(),
+            },
+        ));
+
+        if let Some((x, y)) = self.cursor {
+            let alive = life.get_cell(x, y) == life::LifeCell::Alive;
+            let next = life.next_cell(x, y) == life::LifeCell::Alive;
+
+            rows.push((
+                "Cell",
+                format!("{x},{y} {}", if alive { "alive" } else { "dead" }),
+            ));
+            rows.push((
+                "Neighbors",
+                format!("{} in {}", life.neighbor_count(x, y), life.rule()),
+            ));
+            rows.push((
+                "Next",
+                match (alive, next) {
+                    (true, true) => "survives",
+                    (true, false) => "dies",
+                    (false, true) => "is born",
+                    (false, false) => "stays dead",
+                }
+                .to_string(),
+            ));
+        }
+
+        let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let lines: Vec<Line> = rows
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    format!("{label:label_width$}  ").fg(theme.dialog_dim),
+                    value.clone().into(),
+                ])
+            })
+            .collect();
+
+        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+        let height = lines.len() as u16 + 2;
+
+        if width > inner.width || height > inner.height {
+            return;
+        }
+
+        let area = Rect::new(inner.right() - width, inner.y, width, height);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(Line::from(" Info ".bold()))
+                    .padding(Padding::horizontal(1))
+                    .border_set(border::THICK)
+                    .border_style(Style::new().fg(theme.dialog_border)),
+            )
+            .render(area, buf);
+    }
+}
+
+/// Get the character for a tile of cells that fit in one character.
+///
+/// `set` says whether the cell at an offset into the tile should be drawn. When zoomed in, the
+/// tile is a single cell. `glyph` is used for single cells at normal zoom.
+fn tile_glyph(zoom: Zoom, glyph: char, set: impl Fn(usize, usize) -> bool) -> Option<char> {
+    match zoom {
+        Zoom::Normal => set(0, 0).then_some(glyph),
+
+        Zoom::Block(..) => set(0, 0).then_some('█'),
+
+        Zoom::HalfBlock => match (set(0, 0), set(0, 1)) {
+            (false, false) => None,
+            (true, false) => Some('▀'),
+            (false, true) => Some('▄'),
+            (true, true) => Some('█'),
+        },
+
+        Zoom::Quadrant => {
+            let bits = set(0, 0) as usize
+                | (set(1, 0) as usize) << 1
+                | (set(0, 1) as usize) << 2
+                | (set(1, 1) as usize) << 3;
+
+            (bits != 0).then(|| QUADRANTS[bits])
+        }
+
+        Zoom::Braille => {
+            let mut bits = 0;
+
+            for (i, (bx, by)) in BRAILLE_DOTS.iter().enumerate() {
+                if set(*bx, *by) {
+                    bits |= 1 << i;
+                }
+            }
+
+            (bits != 0).then(|| char::from_u32(0x2800 + bits).unwrap())
+        }
+
+        Zoom::Density(n) => {
+            let mut count = 0;
+
+            for y in 0..n {
+                for x in 0..n {
+                    count += set(x, y) as usize;
+                }
+            }
+
+            // Any live cell at all should show up
+            (count != 0).then(|| SHADES[(count * SHADES.len()).div_ceil(n * n) - 1])
+        }
+    }
 }
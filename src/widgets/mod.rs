@@ -2,4 +2,4 @@ mod help;
 mod life;
 
 pub use help::HelpWidget;
-pub use life::LifeWidget;
+pub use life::{CellColoring, LifeWidget, Preview};
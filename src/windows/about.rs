@@ -1,8 +1,9 @@
-use crate::{AppCommand, AppEvent, AppEventType, util, windows::WindowDrawResult};
-use crossterm::event::{Event, KeyEventKind};
+use crate::{AppCommand, AppEvent, AppEventType, theme::Theme, util, windows::WindowDrawResult};
+use crossterm::event::{Event, KeyEventKind, MouseEventKind};
 use ratatui::{
     layout::Alignment,
     prelude::Stylize,
+    style::Style,
     symbols::border,
     text::Line,
     widgets::{Block, Clear, Padding, Paragraph, Wrap},
@@ -18,19 +19,19 @@ impl AboutWindow {
     }
 
     /// Draw the About Window.
-    pub fn draw(&mut self, frame: &mut ratatui::Frame) -> Option<WindowDrawResult> {
+    pub fn draw(&mut self, frame: &mut ratatui::Frame, theme: &Theme) -> Option<WindowDrawResult> {
         let area = util::centered_area(40, 12, frame);
 
         let text = vec![
             "A Rust/Ratatui implementation of Conway's Game of Life"
                 .bold()
-                .fg(util::rgb6_to_indexed(3, 3, 5))
+                .fg(theme.dialog_key)
                 .into(),
             "".into(),
             "Written by Beej Jorgensen <beej@beej.us>".into(),
             "".into(),
             "Released to the public domain under the terms of the Unlicense"
-                .fg(util::gray_to_indexed(17))
+                .fg(theme.dialog_dim)
                 .into(),
         ];
 
@@ -38,7 +39,8 @@ impl AboutWindow {
             .title(Line::from(" About ".bold()))
             .title_bottom(Line::from(" Press any key ").centered())
             .padding(Padding::new(2, 2, 1, 1))
-            .border_set(border::THICK);
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(theme.dialog_border));
 
         let paragraph = Paragraph::new(text)
             .block(about_block)
@@ -60,6 +62,12 @@ impl AboutWindow {
                     Some(AppCommand::CloseChildWindow)
                 }
 
+                // Clicking anywhere closes the window
+                Event::Mouse(mouse_event) => match mouse_event.kind {
+                    MouseEventKind::Down(_) => Some(AppCommand::CloseChildWindow),
+                    _ => None,
+                },
+
                 _ => None,
             },
 
@@ -0,0 +1,174 @@
+use crate::{
+    AppCommand, AppEvent, AppEventType,
+    census::Census,
+    keymap::{Action, Keymap},
+    theme::Theme,
+    util,
+    windows::WindowDrawResult,
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseEventKind};
+use ratatui::{
+    prelude::Stylize,
+    style::Style,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Padding, Paragraph},
+};
+use std::fs;
+
+/// Actions for keys in the Census window, as well as the page keys, `w` to export, and Enter to
+/// close.
+pub(super) const ACTIONS: [Action; 3] = [Action::MoveUp, Action::MoveDown, Action::Quit];
+
+/// Window to show a census of the objects on the grid.
+pub struct CensusWindow {
+    /// The census to show.
+    census: Census,
+
+    /// Lines scrolled off the top.
+    scroll: u16,
+
+    /// Most lines that can be scrolled, as of the last draw.
+    max_scroll: u16,
+
+    /// Result of the last export, shown until the next key.
+    message: Option<String>,
+}
+
+impl CensusWindow {
+    /// Create a new Census window.
+    pub fn new(census: Census) -> Self {
+        Self {
+            census,
+            scroll: 0,
+            max_scroll: 0,
+            message: None,
+        }
+    }
+
+    /// Draw the Census Window.
+    pub fn draw(&mut self, frame: &mut ratatui::Frame, theme: &Theme) -> Option<WindowDrawResult> {
+        let text = self.census.to_text();
+        let lines: Vec<&str> = text.lines().collect();
+
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as u16
+            + 6;
+        let width = width.clamp(40, frame.area().width);
+        let height = (lines.len() as u16 + 4).min(frame.area().height);
+        let area = util::centered_area(width, height, frame);
+
+        let mut block = Block::bordered()
+            .title(Line::from(" Census ".bold()))
+            .title_bottom(Line::from(" ↑↓→Scroll | w→Export | Esc→Close ").centered())
+            .padding(Padding::new(2, 2, 1, 1))
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(theme.dialog_border));
+
+        if let Some(message) = &self.message {
+            block = block.title(Line::from(format!(" {message} ")).right_aligned());
+        }
+
+        self.max_scroll = (lines.len() as u16).saturating_sub(block.inner(area).height);
+        self.scroll = self.scroll.min(self.max_scroll);
+
+        // The heading and column titles are dimmed, the rows aren't
+        let lines: Vec<Line> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                if i < 3 {
+                    Line::from(line.fg(theme.dialog_dim))
+                } else {
+                    Line::from(*line)
+                }
+            })
+            .collect();
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(block),
+            area,
+        );
+
+        Some(WindowDrawResult::cursor_hide())
+    }
+
+    /// Write the census to a new text file in the current directory.
+    fn export(&mut self) {
+        let path = util::timestamped_file_name("rustlife-census", "txt");
+
+        self.message = Some(match fs::write(&path, self.census.to_text()) {
+            Ok(()) => format!("Saved {path}"),
+            Err(err) => format!("Can't save {path}: {err}"),
+        });
+    }
+
+    /// Handle app events for the Census Window.
+    pub fn handle_app_event(
+        &mut self,
+        app_event: &mut AppEvent,
+        keymap: &Keymap,
+    ) -> Option<AppCommand> {
+        match &app_event.event_type {
+            AppEventType::Event(e) => match e {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    app_event.propagate = false;
+                    self.message = None;
+
+                    match (keymap.action(key_event, &ACTIONS), key_event.code) {
+                        (Some(Action::MoveUp), _) => {
+                            self.scroll = self.scroll.saturating_sub(1);
+                        }
+
+                        (Some(Action::MoveDown), _) => {
+                            self.scroll = (self.scroll + 1).min(self.max_scroll);
+                        }
+
+                        (Some(Action::Quit), _) | (_, KeyCode::Enter) => {
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        (_, KeyCode::PageUp) => {
+                            self.scroll = self.scroll.saturating_sub(10);
+                        }
+
+                        (_, KeyCode::PageDown) => {
+                            self.scroll = (self.scroll + 10).min(self.max_scroll);
+                        }
+
+                        (_, KeyCode::Char('w')) => self.export(),
+
+                        _ => (),
+                    }
+
+                    None
+                }
+
+                // Scrolling scrolls the list
+                Event::Mouse(mouse_event) => {
+                    match mouse_event.kind {
+                        MouseEventKind::ScrollUp => {
+                            self.scroll = self.scroll.saturating_sub(1);
+                        }
+
+                        MouseEventKind::ScrollDown => {
+                            self.scroll = (self.scroll + 1).min(self.max_scroll);
+                        }
+
+                        _ => (),
+                    }
+
+                    None
+                }
+
+                _ => None,
+            },
+
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    AppCommand, AppEvent, AppEventType,
+    keymap::{Action, Keymap},
+    life::{Life, Stats},
+    theme::Theme,
+    util,
+    windows::WindowDrawResult,
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    prelude::Stylize,
+    style::Style,
+    symbols::{self, border},
+    text::Line,
+    widgets::{Axis, Block, Chart, Clear, Dataset, GraphType},
+};
+use std::fs;
+
+/// Actions for keys in the Graph window, as well as `0` to reset the view and `w` to export.
+pub(super) const ACTIONS: [Action; 8] = [
+    Action::ZoomIn,
+    Action::ZoomOut,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::Graph,
+    Action::Quit,
+];
+
+/// Fewest generations to show across the chart when zoomed in.
+const MIN_SPAN: u64 = 10;
+
+/// Most the population axis can be zoomed in.
+const MAX_Y_ZOOM: u32 = 64;
+
+/// Window to plot the population over the generations.
+pub struct GraphWindow {
+    /// Generations shown across the chart, or `None` for all of them.
+    span: Option<u64>,
+
+    /// Generations the right edge of the chart is before the latest one.
+    offset: u64,
+
+    /// How many times the population axis is magnified.
+    y_zoom: u32,
+
+    /// Result of the last export, shown until the next key.
+    message: Option<String>,
+}
+
+impl GraphWindow {
+    /// Create a new Graph window showing the whole history.
+    pub fn new() -> Self {
+        Self {
+            span: None,
+            offset: 0,
+            y_zoom: 1,
+            message: None,
+        }
+    }
+
+    /// Get the first and last generations in view.
+    fn range(&self, life: &Life) -> (u64, u64) {
+        let history = life.history();
+        let first = history.front().map_or(0, |stats| stats.generation);
+        let last = history.back().map_or(0, |stats| stats.generation);
+
+        let right = last.saturating_sub(self.offset).max(first);
+        let left = self
+            .span
+            .map_or(first, |span| right.saturating_sub(span).max(first));
+
+        (left, right)
+    }
+
+    /// Draw the Graph Window.
+    pub fn draw(
+        &mut self,
+        frame: &mut ratatui::Frame,
+        theme: &Theme,
+        life: &Life,
+    ) -> Option<WindowDrawResult> {
+        let area = frame.area();
+        let (left, right) = self.range(life);
+
+        let visible: Vec<&Stats> = life
+            .history()
+            .iter()
+            .filter(|stats| (left..=right).contains(&stats.generation))
+            .collect();
+
+        // Braille has two dots across each character, and there's no use plotting more than that
+        let stride = (visible.len() / (area.width as usize * 2).max(1)).max(1);
+        let points: Vec<(f64, f64)> = visible
+            .iter()
+            .step_by(stride)
+            .chain(visible.last())
+            .map(|stats| (stats.generation as f64, stats.population as f64))
+            .collect();
+
+        let max_population = visible.iter().map(|stats| stats.population).max();
+        let top = (max_population.unwrap_or(0) as f64 / self.y_zoom as f64).max(1.0);
+        let right_bound = right.max(left + 1);
+
+        let mut block = Block::bordered()
+            .title(Line::from(" Population ".bold()).centered())
+            .title_bottom(
+                Line::from(" ←→→Pan | +-→Zoom | ↑↓→Zoom population | 0→Reset | w→Export CSV ")
+                    .centered(),
+            )
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(theme.dialog_border));
+
+        if let Some(message) = &self.message {
+            block = block.title(Line::from(format!(" {message} ")).right_aligned());
+        }
+
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(theme.alive))
+            .data(&points);
+
+        let chart = Chart::new(vec![dataset])
+            .block(block)
+            .x_axis(
+                Axis::default()
+                    .title("Generation".fg(theme.dialog_dim))
+                    .style(Style::new().fg(theme.dialog_dim))
+                    .bounds([left as f64, right_bound as f64])
+                    .labels([
+                        left.to_string(),
+                        (left + (right_bound - left) / 2).to_string(),
+                        right_bound.to_string(),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("Cells".fg(theme.dialog_dim))
+                    .style(Style::new().fg(theme.dialog_dim))
+                    .bounds([0.0, top])
+                    .labels([
+                        "0".to_string(),
+                        format!("{:.0}", top / 2.0),
+                        format!("{top:.0}"),
+                    ]),
+            );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(chart, area);
+
+        Some(WindowDrawResult::cursor_hide())
+    }
+
+    /// Write the whole history to a new CSV file in the current directory.
+    fn export(&mut self, life: &Life) {
+        let path = util::timestamped_file_name("rustlife-population", "csv");
+
+        let mut csv = String::from("generation,population,births,deaths\n");
+
+        for stats in life.history() {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                stats.generation, stats.population, stats.births, stats.deaths
+            ));
+        }
+
+        self.message = Some(match fs::write(&path, csv) {
+            Ok(()) => format!("Saved {path}"),
+            Err(err) => format!("Can't save {path}: {err}"),
+        });
+    }
+
+    /// Handle app events for the Graph Window.
+    pub fn handle_app_event(
+        &mut self,
+        app_event: &mut AppEvent,
+        life: &Life,
+        keymap: &Keymap,
+    ) -> Option<AppCommand> {
+        match &app_event.event_type {
+            AppEventType::Event(e) => match e {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    app_event.propagate = false;
+                    self.message = None;
+
+                    let (left, right) = self.range(life);
+                    let shown = right - left;
+                    let total = life.history().back().map_or(0, |stats| stats.generation)
+                        - life.history().front().map_or(0, |stats| stats.generation);
+
+                    match (keymap.action(key_event, &ACTIONS), key_event.code) {
+                        (Some(Action::ZoomIn), _) => {
+                            self.span = Some((shown / 2).max(MIN_SPAN));
+                        }
+
+                        (Some(Action::ZoomOut), _) => {
+                            self.span = self.span.map(|span| span * 2).filter(|span| *span < total);
+                        }
+
+                        (Some(Action::MoveLeft), _) => {
+                            self.offset = (self.offset + (shown / 4).max(1)).min(total);
+                        }
+
+                        (Some(Action::MoveRight), _) => {
+                            self.offset = self.offset.saturating_sub((shown / 4).max(1));
+                        }
+
+                        (Some(Action::MoveUp), _) => {
+                            self.y_zoom = (self.y_zoom * 2).min(MAX_Y_ZOOM);
+                        }
+
+                        (Some(Action::MoveDown), _) => {
+                            self.y_zoom = (self.y_zoom / 2).max(1);
+                        }
+
+                        (Some(Action::Graph | Action::Quit), _) => {
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        (_, KeyCode::Char('0')) => *self = Self::new(),
+
+                        (_, KeyCode::Char('w')) => self.export(life),
+
+                        _ => (),
+                    }
+
+                    None
+                }
+
+                _ => None,
+            },
+
+            _ => None,
+        }
+    }
+}
@@ -1,22 +1,48 @@
 use crate::{
-    AppCommand, AppEvent, AppEventType, util, widgets::HelpWidget, windows::WindowDrawResult,
+    AppCommand, AppEvent, AppEventType,
+    keymap::{Action, Keymap},
+    theme::Theme,
+    util,
+    widgets::HelpWidget,
+    windows::WindowDrawResult,
 };
-use crossterm::event::{Event, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseEventKind};
+
+/// Actions for keys in the Help window, as well as the page keys.
+pub(super) const ACTIONS: [Action; 2] = [Action::MoveUp, Action::MoveDown];
 
 /// Window to show the HelpWidget.
-pub struct HelpWindow;
+pub struct HelpWindow {
+    /// Keys to list.
+    keymap: Keymap,
+
+    /// Lines scrolled off the top.
+    scroll: u16,
+
+    /// Most lines that can be scrolled, as of the last draw.
+    max_scroll: u16,
+}
 
 impl HelpWindow {
-    /// Make a new HelpWindow.
-    pub fn new() -> Self {
-        Self {}
+    /// Make a new HelpWindow listing the keys in a keymap.
+    pub fn new(keymap: &Keymap) -> Self {
+        Self {
+            keymap: keymap.clone(),
+            scroll: 0,
+            max_scroll: 0,
+        }
     }
 
     /// Draw the Help Window.
-    pub fn draw(&mut self, frame: &mut ratatui::Frame) -> Option<WindowDrawResult> {
-        let area = util::centered_area(43, 17, frame);
-        let help = HelpWidget::new();
-        frame.render_widget(help, area);
+    pub fn draw(&mut self, frame: &mut ratatui::Frame, theme: &Theme) -> Option<WindowDrawResult> {
+        let help = HelpWidget::new(theme, &self.keymap);
+        let size = help.size(frame.area().as_size());
+        let area = util::centered_area(size.width, size.height, frame);
+
+        self.max_scroll = help.max_scroll(area);
+        self.scroll = self.scroll.min(self.max_scroll);
+
+        frame.render_widget(help.scroll(self.scroll), area);
 
         Some(WindowDrawResult::cursor_hide())
     }
@@ -27,9 +53,50 @@ impl HelpWindow {
             AppEventType::Event(e) => match e {
                 Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                     app_event.propagate = false;
-                    Some(AppCommand::CloseChildWindow)
+
+                    // Scroll if there's more to see, otherwise any key closes
+                    match (self.keymap.action(key_event, &ACTIONS), key_event.code) {
+                        (Some(Action::MoveUp), _) if self.max_scroll > 0 => {
+                            self.scroll = self.scroll.saturating_sub(1);
+                            None
+                        }
+
+                        (Some(Action::MoveDown), _) if self.max_scroll > 0 => {
+                            self.scroll = (self.scroll + 1).min(self.max_scroll);
+                            None
+                        }
+
+                        (_, KeyCode::PageUp) if self.max_scroll > 0 => {
+                            self.scroll = self.scroll.saturating_sub(10);
+                            None
+                        }
+
+                        (_, KeyCode::PageDown) if self.max_scroll > 0 => {
+                            self.scroll = (self.scroll + 10).min(self.max_scroll);
+                            None
+                        }
+
+                        _ => Some(AppCommand::CloseChildWindow),
+                    }
                 }
 
+                // Clicking anywhere closes the window
+                Event::Mouse(mouse_event) => match mouse_event.kind {
+                    MouseEventKind::Down(_) => Some(AppCommand::CloseChildWindow),
+
+                    MouseEventKind::ScrollUp => {
+                        self.scroll = self.scroll.saturating_sub(1);
+                        None
+                    }
+
+                    MouseEventKind::ScrollDown => {
+                        self.scroll = (self.scroll + 1).min(self.max_scroll);
+                        None
+                    }
+
+                    _ => None,
+                },
+
                 _ => None,
             },
 
@@ -0,0 +1,295 @@
+use crate::{
+    AppCommand, AppEvent, AppEventType,
+    keymap::{Action, Keymap},
+    library::LIBRARY,
+    life::Life,
+    pattern::{PasteMode, Pattern, Transform},
+    theme::Theme,
+    util,
+    viewport::Viewport,
+    widgets::LifeWidget,
+    windows::WindowDrawResult,
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    prelude::Stylize,
+    style::{Modifier, Style},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Padding, Paragraph},
+};
+
+/// Actions for keys in the Library window, as well as Home and End to jump to either end of the
+/// list and Enter to stamp.
+pub(super) const ACTIONS: [Action; 7] = [
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::RotateCw,
+    Action::RotateCcw,
+    Action::FlipHorizontal,
+    Action::Library,
+    Action::Quit,
+];
+
+/// Window to browse the pattern library and pick a pattern to stamp.
+pub struct LibraryWindow {
+    /// Index into the library of the highlighted pattern.
+    selected: usize,
+
+    /// Number of 90° clockwise turns to apply.
+    rotation: usize,
+
+    /// True if the pattern is mirrored left to right before it's turned.
+    flipped: bool,
+
+    /// Where the list was drawn and how far it was scrolled, to find the pattern clicked.
+    list_area: Rect,
+    list_scroll: usize,
+
+    /// Pattern picked to stamp, once the user has chosen.
+    chosen: Option<Pattern>,
+}
+
+impl LibraryWindow {
+    /// Create a new Library window.
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            rotation: 0,
+            flipped: false,
+            list_area: Rect::default(),
+            list_scroll: 0,
+            chosen: None,
+        }
+    }
+
+    /// Take the pattern the user chose, if any.
+    pub fn take_chosen(&mut self) -> Option<Pattern> {
+        self.chosen.take()
+    }
+
+    /// Get the highlighted pattern in its current orientation.
+    fn oriented_pattern(&self) -> Pattern {
+        let mut pattern = LIBRARY[self.selected].pattern();
+
+        if self.flipped {
+            pattern = pattern.transformed(Transform::FlipHorizontal);
+        }
+
+        for _ in 0..self.rotation {
+            pattern = pattern.transformed(Transform::RotateCw);
+        }
+
+        pattern
+    }
+
+    /// Draw the Library Window.
+    pub fn draw(&mut self, frame: &mut ratatui::Frame, theme: &Theme) -> Option<WindowDrawResult> {
+        let area = util::centered_area(60, 20, frame);
+
+        let block = Block::bordered()
+            .title(Line::from(" Pattern Library ".bold()))
+            .title_bottom(Line::from(" ↑↓→Choose | <>→Rotate | |→Flip | Enter→Stamp ").centered())
+            .padding(Padding::new(1, 1, 0, 0))
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(theme.dialog_border));
+
+        let inner = block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Length(21), Constraint::Fill(1)]).areas(inner);
+
+        // List the patterns under their category headings
+        let mut lines = Vec::new();
+        let mut selected_line = 0;
+
+        for (i, entry) in LIBRARY.iter().enumerate() {
+            if i == 0 || LIBRARY[i - 1].category != entry.category {
+                lines.push(Line::from(entry.category.fg(theme.dialog_dim)));
+            }
+
+            if i == self.selected {
+                selected_line = lines.len();
+                lines.push(
+                    Line::from(format!(" {}", entry.name)).style(
+                        Style::new()
+                            .fg(theme.dialog_key)
+                            .add_modifier(Modifier::REVERSED),
+                    ),
+                );
+            } else {
+                lines.push(Line::from(format!(" {}", entry.name)));
+            }
+        }
+
+        // Scroll so the selection is in view, showing the heading above it where possible
+        let scroll = (selected_line + 2).saturating_sub(list_area.height as usize);
+
+        frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), list_area);
+
+        self.list_area = list_area;
+        self.list_scroll = scroll;
+
+        // Describe the pattern under the preview
+        let pattern = self.oriented_pattern();
+
+        let [preview_area, info_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(preview_area);
+
+        let info = format!(
+            "{}x{}, {}°{}",
+            pattern.width(),
+            pattern.height(),
+            self.rotation * 90,
+            if self.flipped { ", flipped" } else { "" }
+        );
+
+        frame.render_widget(
+            Paragraph::new(info.fg(theme.dialog_dim)).centered(),
+            info_area,
+        );
+
+        // Zoom the preview so the whole pattern fits, then center it
+        let mut life = Life::new();
+        let mut viewport = Viewport::new();
+
+        life.init(pattern.width(), pattern.height());
+        life.paste(&pattern, 0, 0, PasteMode::Copy);
+        viewport.area = preview_area;
+        viewport.fit(&life);
+
+        let (area_w, area_h) = viewport.area_cells();
+        let width = area_w.max(pattern.width());
+        let height = area_h.max(pattern.height());
+
+        life.init(width, height);
+        life.paste(
+            &pattern,
+            (width - pattern.width()) / 2,
+            (height - pattern.height()) / 2,
+            PasteMode::Copy,
+        );
+        viewport.x = 0;
+        viewport.y = 0;
+
+        frame.render_widget(
+            LifeWidget::new(&life).viewport(&viewport).theme(theme),
+            preview_area,
+        );
+
+        Some(WindowDrawResult::cursor_hide())
+    }
+
+    /// Select a different pattern, resetting the orientation.
+    fn select(&mut self, selected: usize) {
+        if selected != self.selected {
+            self.selected = selected;
+            self.rotation = 0;
+            self.flipped = false;
+        }
+    }
+
+    /// Handle app events for the Library Window.
+    pub fn handle_app_event(
+        &mut self,
+        app_event: &mut AppEvent,
+        keymap: &Keymap,
+    ) -> Option<AppCommand> {
+        match &app_event.event_type {
+            AppEventType::Event(e) => match e {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    app_event.propagate = false;
+
+                    match (keymap.action(key_event, &ACTIONS), key_event.code) {
+                        (Some(Action::MoveDown), _) => {
+                            self.select((self.selected + 1).min(LIBRARY.len() - 1));
+                        }
+
+                        (Some(Action::MoveUp), _) => {
+                            self.select(self.selected.saturating_sub(1));
+                        }
+
+                        (Some(Action::RotateCw), _) => self.rotation = (self.rotation + 1) % 4,
+
+                        (Some(Action::RotateCcw), _) => self.rotation = (self.rotation + 3) % 4,
+
+                        (Some(Action::FlipHorizontal), _) => self.flipped = !self.flipped,
+
+                        (Some(Action::Library | Action::Quit), _) => {
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        (_, KeyCode::Home) => self.select(0),
+
+                        (_, KeyCode::End) => self.select(LIBRARY.len() - 1),
+
+                        (_, KeyCode::Enter) => {
+                            self.chosen = Some(self.oriented_pattern());
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        _ => (),
+                    }
+
+                    None
+                }
+
+                // Scrolling moves through the list, and clicking a pattern highlights it
+                Event::Mouse(mouse_event) => {
+                    match mouse_event.kind {
+                        MouseEventKind::ScrollDown => {
+                            self.select((self.selected + 1).min(LIBRARY.len() - 1));
+                        }
+
+                        MouseEventKind::ScrollUp => {
+                            self.select(self.selected.saturating_sub(1));
+                        }
+
+                        MouseEventKind::Down(MouseButton::Left) => {
+                            if let Some(i) = util::clicked_row(
+                                self.list_area,
+                                mouse_event.column,
+                                mouse_event.row,
+                            )
+                            .and_then(|row| entry_at_line(row + self.list_scroll))
+                            {
+                                self.select(i);
+                            }
+                        }
+
+                        _ => (),
+                    }
+
+                    None
+                }
+
+                _ => None,
+            },
+
+            _ => None,
+        }
+    }
+}
+
+/// Find the library entry on a line of the list, counting the category headings above them.
+fn entry_at_line(line: usize) -> Option<usize> {
+    let mut lines = 0;
+
+    for (i, entry) in LIBRARY.iter().enumerate() {
+        if i == 0 || LIBRARY[i - 1].category != entry.category {
+            lines += 1;
+        }
+
+        if lines == line {
+            return Some(i);
+        }
+
+        lines += 1;
+    }
+
+    None
+}
@@ -1,64 +1,347 @@
 use crate::{
     AppCommand, AppEvent, AppEventType,
-    life::Life,
+    census::{Census, Names},
+    command::{Command, CommandLine, CommandLineResult, MAX_SPEED},
+    config::Config,
+    keymap::{Action, Keymap},
+    life::{Life, LifeCell},
+    pattern::{MAX_SIZE, PasteMode, Pattern, Transform},
+    period::Period,
+    rule::Rule,
+    session::Session,
+    shape::Shape,
+    soup::{Soup, SoupOrigin},
+    theme::Theme,
+    tracker::Tracker,
     util,
-    widgets::LifeWidget,
-    windows::{AboutWindow, HelpWindow, WindowDrawResult},
+    viewport::Viewport,
+    widgets::{CellColoring, LifeWidget, Preview},
+    windows::{
+        AboutWindow, CensusWindow, GraphWindow, HelpWindow, LibraryWindow, SettingsWindow,
+        SoupWindow, WindowDrawResult,
+    },
 };
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
-use ratatui::{prelude::Stylize, symbols::border, text::Line, widgets::Block};
-use std::time::Duration;
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::{
+    layout::{Rect, Size},
+    prelude::Stylize,
+    style::Style,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+/// Generations of population shown in the status bar.
+const SPARKLINE_LENGTH: usize = 16;
+
+/// Most commands to remember in the command line history.
+const COMMAND_HISTORY_LENGTH: usize = 100;
+
+/// Longest time to spend stepping for `:gen` before drawing and checking for keys.
+const GEN_CHUNK_TIME: Duration = Duration::from_millis(20);
+
+/// Actions for keys when not in a mode.
+pub(super) const NORMAL_ACTIONS: [Action; 46] = [
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::MoveUpLeft,
+    Action::MoveUpRight,
+    Action::MoveDownLeft,
+    Action::MoveDownRight,
+    Action::PanUp,
+    Action::PanDown,
+    Action::PanLeft,
+    Action::PanRight,
+    Action::PanUpLeft,
+    Action::PanUpRight,
+    Action::PanDownLeft,
+    Action::PanDownRight,
+    Action::CenterOnCursor,
+    Action::FitPattern,
+    Action::ZoomIn,
+    Action::ZoomOut,
+    Action::ToggleGridlines,
+    Action::CycleColoring,
+    Action::CycleTheme,
+    Action::Graph,
+    Action::Period,
+    Action::Census,
+    Action::Track,
+    Action::Info,
+    Action::NextGeneration,
+    Action::Step,
+    Action::Run,
+    Action::ToggleCell,
+    Action::Clear,
+    Action::RandomSoup,
+    Action::SoupSettings,
+    Action::Save,
+    Action::CommandLine,
+    Action::Select,
+    Action::Paste,
+    Action::Draw,
+    Action::Fill,
+    Action::Library,
+    Action::Help,
+    Action::Settings,
+    Action::About,
+    Action::Quit,
+];
+
+/// Actions for keys while selecting, which take priority over the normal ones.
+pub(super) const SELECT_ACTIONS: [Action; 14] = [
+    Action::Cancel,
+    Action::Select,
+    Action::Copy,
+    Action::Cut,
+    Action::ClearSelection,
+    Action::ClearOutside,
+    Action::Period,
+    Action::Track,
+    Action::RotateCw,
+    Action::RotateCcw,
+    Action::Rotate180,
+    Action::FlipHorizontal,
+    Action::FlipVertical,
+    Action::Transpose,
+];
+
+/// Actions for keys while pasting.
+pub(super) const PASTE_ACTIONS: [Action; 10] = [
+    Action::Cancel,
+    Action::RotateCw,
+    Action::RotateCcw,
+    Action::Rotate180,
+    Action::FlipHorizontal,
+    Action::FlipVertical,
+    Action::Transpose,
+    Action::PasteMode,
+    Action::Place,
+    Action::Paste,
+];
+
+/// Actions for keys while drawing.
+pub(super) const DRAW_ACTIONS: [Action; 4] = [
+    Action::Cancel,
+    Action::Draw,
+    Action::NextShape,
+    Action::Place,
+];
+
+/// Actions for keys while filling.
+pub(super) const FILL_ACTIONS: [Action; 3] = [Action::Cancel, Action::Fill, Action::Place];
 
 /// Child Windows for the Life window.
 enum LifeChildWindow {
     Help(HelpWindow),
     About(AboutWindow),
+    Library(LibraryWindow),
+    Soup(SoupWindow),
+    Settings(SettingsWindow),
+    Graph(GraphWindow),
+    Census(CensusWindow),
 }
 
 impl LifeChildWindow {
     /// Draw Life child windows.
-    fn draw(&mut self, frame: &mut ratatui::Frame) -> Option<WindowDrawResult> {
+    fn draw(
+        &mut self,
+        frame: &mut ratatui::Frame,
+        theme: &Theme,
+        life: &Life,
+    ) -> Option<WindowDrawResult> {
         match self {
-            LifeChildWindow::Help(win) => win.draw(frame),
-            LifeChildWindow::About(win) => win.draw(frame),
+            LifeChildWindow::Help(win) => win.draw(frame, theme),
+            LifeChildWindow::About(win) => win.draw(frame, theme),
+            LifeChildWindow::Library(win) => win.draw(frame, theme),
+            LifeChildWindow::Soup(win) => win.draw(frame, theme),
+            LifeChildWindow::Settings(win) => win.draw(frame, theme),
+            LifeChildWindow::Graph(win) => win.draw(frame, theme, life),
+            LifeChildWindow::Census(win) => win.draw(frame, theme),
         }
     }
 
     /// Handle events on Life child windows.
-    fn handle_app_event(&mut self, app_event: &mut AppEvent) -> Option<AppCommand> {
+    ///
+    /// Windows with keys that do the same as keys for the Life window look them up in the keymap.
+    fn handle_app_event(
+        &mut self,
+        app_event: &mut AppEvent,
+        life: &Life,
+        keymap: &Keymap,
+    ) -> Option<AppCommand> {
+        // No mouse events get through to the Life grid underneath
+        if let AppEventType::Event(Event::Mouse(_)) = app_event.event_type {
+            app_event.propagate = false;
+        }
+
         match self {
             LifeChildWindow::Help(win) => win.handle_app_event(app_event),
             LifeChildWindow::About(win) => win.handle_app_event(app_event),
+            LifeChildWindow::Library(win) => win.handle_app_event(app_event, keymap),
+            LifeChildWindow::Soup(win) => win.handle_app_event(app_event, keymap),
+            LifeChildWindow::Settings(win) => win.handle_app_event(app_event, keymap),
+            LifeChildWindow::Graph(win) => win.handle_app_event(app_event, life, keymap),
+            LifeChildWindow::Census(win) => win.handle_app_event(app_event, keymap),
         }
     }
 }
 
+/// What the keyboard is doing in the Life window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// Moving around and editing cells.
+    Normal,
+
+    /// Selecting a rectangle from an anchor cell to the cursor.
+    ///
+    /// A transformed selection can be more than half the universe across, which the short way
+    /// from the anchor to the cursor can't describe, so it's kept as `(x, y, width, height)`
+    /// until the cursor moves off its far corner.
+    Select {
+        anchor_x: usize,
+        anchor_y: usize,
+        transformed: Option<(usize, usize, usize, usize)>,
+    },
+
+    /// Moving the clipboard around with the cursor before pasting it.
+    Paste(PasteMode),
+
+    /// Drawing a shape from an anchor cell to the cursor.
+    Draw {
+        anchor_x: usize,
+        anchor_y: usize,
+        shape: Shape,
+    },
+
+    /// Flood filling from the cursor.
+    Fill,
+}
+
 /// Window to show the Life grid.
 pub struct LifeWindow {
-    /// Cursor X position.
-    cursor_x: u16,
+    /// Cursor X position in the universe.
+    cursor_x: usize,
+
+    /// Cursor Y position in the universe.
+    cursor_y: usize,
 
-    /// Cursor Y position.
-    cursor_y: u16,
+    /// The part of the universe that's on screen.
+    viewport: Viewport,
+
+    /// How to color the cells.
+    coloring: CellColoring,
+
+    /// Colors and glyphs.
+    theme: Theme,
 
     /// True if the life simuation is in continuous-run mode.
     pub running: bool,
 
+    /// Generations per second when running.
+    speed: u32,
+
     /// Tracker for prefix count on some commands
     count: u32,
 
-    /// Help and About Windows
+    /// Whether we're selecting or pasting.
+    mode: Mode,
+
+    /// Copied cells.
+    clipboard: Option<Pattern>,
+
+    /// How the cells were repeating as of the last step, if they were.
+    period: Option<Period>,
+
+    /// Generations `:gen` still has to step, done a chunk at a time on ticks so a key can stop
+    /// it.
+    generations_left: u32,
+
+    /// Spaceship the view is following, if any.
+    tracker: Option<Tracker>,
+
+    /// Names of known objects for the census, with the rule they were worked out for, so they're
+    /// only worked out again when the rule changes.
+    census_names: Option<(Rule, Names)>,
+
+    /// True if the info overlay is showing.
+    info: bool,
+
+    /// True to show which cells will be born and die next generation while paused.
+    next_generation: bool,
+
+    /// Settings for random soups.
+    soup: Soup,
+
+    /// How the last random soup was made, if there's been one since the grid was cleared.
+    soup_origin: Option<SoupOrigin>,
+
+    /// Generation and edit count of the grid right after the last soup was made, to tell how far
+    /// it's come since and if it's been edited.
+    soup_made: (u64, u64),
+
+    /// Message for the status bar, shown until the next key.
+    message: Option<String>,
+
+    /// The `:` command line, if it's open.
+    command_line: Option<CommandLine>,
+
+    /// Commands entered on the command line, oldest first.
+    command_history: Vec<String>,
+
+    /// Last cell painted by a mouse drag, unwrapped so lines don't jump across the universe.
+    mouse_paint: Option<(isize, isize)>,
+
+    /// Last screen position of a middle-button pan drag.
+    mouse_pan: Option<(u16, u16)>,
+
+    /// Settings from the config file, including what each key does.
+    config: Config,
+
+    /// Help, About, Library, Soup, Settings, Graph, and Census windows
     child_window: Option<LifeChildWindow>,
 }
 
 impl LifeWindow {
-    /// Create a new LifeWindow.
-    pub fn new() -> LifeWindow {
+    /// Create a new LifeWindow starting with the settings from the config file.
+    pub fn new(config: Config) -> LifeWindow {
+        let mut viewport = Viewport::new();
+        viewport.gridlines = config.appearance.gridlines;
+
         LifeWindow {
             cursor_x: 0,
             cursor_y: 0,
+            viewport,
+            coloring: CellColoring::Plain,
+            theme: config.appearance.theme(config.appearance.theme),
             running: false,
+            speed: config.speed,
             count: 0,
+            mode: Mode::Normal,
+            clipboard: None,
+            period: None,
+            generations_left: 0,
+            tracker: None,
+            census_names: None,
+            info: false,
+            next_generation: false,
+            soup: config.soup,
+            soup_origin: None,
+            soup_made: (0, 0),
+            message: None,
+            command_line: None,
+            command_history: Vec::new(),
+            mouse_paint: None,
+            mouse_pan: None,
+            config,
             child_window: None,
         }
     }
@@ -68,87 +351,238 @@ impl LifeWindow {
         let mut app_command = None;
         let old_running = self.running;
 
-        match key_event.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
+        self.message = None;
+
+        // Any key stops `:gen` partway
+        if self.generations_left > 0 {
+            self.generations_left = 0;
+            self.message = Some(format!("Stopped at generation {}", life.generation()));
+            return Some(AppCommand::TimerStop);
+        }
+
+        if self.command_line.is_some() {
+            return self.handle_command_line_key(key_event, life);
+        }
+
+        if self.handle_mode_key(key_event, life) {
+            self.count = 0;
+            return self.running_command(old_running);
+        }
+
+        match self.config.keymap.action(key_event, &NORMAL_ACTIONS) {
+            Some(Action::Quit) => {
                 app_command = Some(AppCommand::Quit);
             }
 
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.cursor_y -= 1;
+            Some(Action::MoveUp) => {
+                self.move_cursor(0, -1, life);
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.cursor_y += 1;
+            Some(Action::MoveDown) => {
+                if self.count > 0 {
+                    self.running = false;
+                    life.vertical_line(self.cursor_x, self.cursor_y, self.count);
+                } else {
+                    self.move_cursor(0, 1, life);
+                }
             }
-            KeyCode::Left | KeyCode::Char('h') => {
+            Some(Action::MoveLeft) => {
                 if self.count > 0 {
                     self.running = false;
-                    life.horizontal_line(
-                        self.cursor_x as usize - 1,
-                        self.cursor_y as usize - 1,
-                        self.count,
-                    );
+                    life.horizontal_line(self.cursor_x, self.cursor_y, self.count);
                 } else {
-                    self.cursor_x -= 1;
+                    self.move_cursor(-1, 0, life);
                 }
             }
-            KeyCode::Right | KeyCode::Char('l') => {
-                self.cursor_x += 1;
+            Some(Action::MoveRight) => {
+                self.move_cursor(1, 0, life);
+            }
+            Some(Action::MoveUpLeft) => {
+                self.move_cursor(-1, -1, life);
+            }
+            Some(Action::MoveUpRight) => {
+                self.move_cursor(1, -1, life);
+            }
+            Some(Action::MoveDownLeft) => {
+                self.move_cursor(-1, 1, life);
+            }
+            Some(Action::MoveDownRight) => {
+                self.move_cursor(1, 1, life);
+            }
+
+            Some(Action::PanUp) => self.pan(0, -1, life),
+            Some(Action::PanDown) => self.pan(0, 1, life),
+            Some(Action::PanLeft) => self.pan(-1, 0, life),
+            Some(Action::PanRight) => self.pan(1, 0, life),
+            Some(Action::PanUpLeft) => self.pan(-1, -1, life),
+            Some(Action::PanUpRight) => self.pan(1, -1, life),
+            Some(Action::PanDownLeft) => self.pan(-1, 1, life),
+            Some(Action::PanDownRight) => self.pan(1, 1, life),
+
+            Some(Action::ZoomIn) if self.viewport.zoom_in() => {
+                self.zoom_changed(life);
             }
-            KeyCode::Char('y') => {
-                self.cursor_x -= 1;
-                self.cursor_y -= 1;
+
+            Some(Action::ZoomOut) if self.viewport.zoom_out() => {
+                self.zoom_changed(life);
             }
-            KeyCode::Char('u') => {
-                self.cursor_x += 1;
-                self.cursor_y -= 1;
+
+            Some(Action::CycleColoring) => {
+                self.coloring = self.coloring.next();
+                life.set_track_ages(self.coloring != CellColoring::Plain);
             }
-            KeyCode::Char('b') => {
-                self.cursor_x -= 1;
-                self.cursor_y += 1;
+
+            Some(Action::CycleTheme) => {
+                self.theme = self.config.appearance.theme(self.theme.kind.next());
             }
-            KeyCode::Char('n') => {
-                self.cursor_x += 1;
-                self.cursor_y += 1;
+
+            Some(Action::Info) => {
+                self.info = !self.info;
             }
 
-            KeyCode::Char('s') => {
+            Some(Action::NextGeneration) => {
+                self.next_generation = !self.next_generation;
+
+                if self.next_generation && self.running {
+                    self.message = Some("Births and deaths show while paused".to_string());
+                }
+            }
+
+            Some(Action::ToggleGridlines) => {
+                self.viewport.gridlines = !self.viewport.gridlines;
+            }
+
+            Some(Action::CenterOnCursor) => {
+                self.viewport.center_on(self.cursor_x, self.cursor_y, life);
+            }
+
+            Some(Action::FitPattern) if self.viewport.fit(life) => {
+                let (vis_w, vis_h) = self.viewport.visible_cells(life);
+                self.cursor_x = (self.viewport.x + vis_w / 2) % life.width();
+                self.cursor_y = (self.viewport.y + vis_h / 2) % life.height();
+                self.zoom_changed(life);
+            }
+
+            Some(Action::Step) => {
                 self.running = false;
                 life.step();
+                self.check_period(life);
+                self.follow(life);
             }
 
-            KeyCode::Char(' ') | KeyCode::Char('t') => {
+            Some(Action::Period) => {
+                self.message = Some(match life.period() {
+                    Some(period) => self.describe_period(life, period),
+                    None => "Not repeating yet".to_string(),
+                });
+            }
+
+            Some(Action::ToggleCell) => {
                 self.running = false;
-                life.toggle(self.cursor_x as usize - 1, self.cursor_y as usize - 1);
+                life.toggle(self.cursor_x, self.cursor_y);
             }
 
-            KeyCode::Char('c') => {
+            Some(Action::Clear) => {
                 self.running = false;
                 life.clear();
+                self.soup_origin = None;
+            }
+
+            Some(Action::RandomSoup) => {
+                self.randomize(life, None);
             }
 
-            KeyCode::Char('R') => {
-                life.randomize();
+            Some(Action::Save) => {
+                self.save(life, None);
             }
 
-            KeyCode::Char('r') => {
+            Some(Action::SoupSettings) => {
+                self.child_window = Some(LifeChildWindow::Soup(SoupWindow::new(self.soup)));
+            }
+
+            Some(Action::Run) => {
                 self.running = !self.running;
             }
 
-            KeyCode::Char('?') => {
-                self.child_window = Some(LifeChildWindow::Help(HelpWindow::new()));
+            Some(Action::Select) => {
+                self.mode = Mode::Select {
+                    anchor_x: self.cursor_x,
+                    anchor_y: self.cursor_y,
+                    transformed: None,
+                };
+            }
+
+            Some(Action::Paste) if self.clipboard.is_some() => {
+                self.mode = Mode::Paste(PasteMode::Or);
+            }
+
+            Some(Action::Draw) => {
+                self.mode = Mode::Draw {
+                    anchor_x: self.cursor_x,
+                    anchor_y: self.cursor_y,
+                    shape: Shape::Line,
+                };
+            }
+
+            Some(Action::Fill) => {
+                self.mode = Mode::Fill;
+            }
+
+            Some(Action::CommandLine) => {
+                self.command_line = Some(CommandLine::new());
+            }
+
+            Some(Action::Help) => {
+                self.child_window =
+                    Some(LifeChildWindow::Help(HelpWindow::new(&self.config.keymap)));
+            }
+
+            Some(Action::Settings) => {
+                self.child_window =
+                    Some(LifeChildWindow::Settings(SettingsWindow::new(&self.config)));
+            }
+
+            Some(Action::Graph) => {
+                self.child_window = Some(LifeChildWindow::Graph(GraphWindow::new()));
+            }
+
+            Some(Action::Track) => {
+                self.message = Some(match self.tracker.take() {
+                    Some(_) => "Stopped tracking".to_string(),
+                    None => "Select a spaceship to track".to_string(),
+                });
             }
 
-            KeyCode::Char('a') => {
+            Some(Action::Census) => {
+                let rule = life.rule();
+                self.census_names = self
+                    .census_names
+                    .take()
+                    .filter(|(names_rule, _)| *names_rule == rule);
+
+                let (_, names) = self
+                    .census_names
+                    .get_or_insert_with(|| (rule, Names::new(&rule)));
+
+                self.child_window = Some(LifeChildWindow::Census(CensusWindow::new(
+                    Census::take_named(life, names),
+                )));
+            }
+
+            Some(Action::About) => {
                 self.child_window = Some(LifeChildWindow::About(AboutWindow::new()));
             }
 
+            Some(Action::Library) => {
+                self.child_window = Some(LifeChildWindow::Library(LibraryWindow::new()));
+            }
+
             _ => (),
         }
 
         match key_event.code {
             KeyCode::Char(c) if c.is_ascii_digit() => {
                 let value = c.to_digit(10).unwrap();
-                self.count = self.count * 10 + value;
+                self.count = self.count.saturating_mul(10).saturating_add(value);
             }
 
             _ => {
@@ -156,25 +590,895 @@ impl LifeWindow {
             }
         }
 
-        let running_changed = old_running != self.running;
+        self.running_command(old_running).or(app_command)
+    }
+
+    /// Handle keys while the command line is open.
+    fn handle_command_line_key(
+        &mut self,
+        key_event: &KeyEvent,
+        life: &mut Life,
+    ) -> Option<AppCommand> {
+        let command_line = self.command_line.as_mut()?;
+
+        match command_line.handle_key(key_event, &self.command_history) {
+            CommandLineResult::Editing => None,
+
+            CommandLineResult::Cancel => {
+                self.command_line = None;
+                None
+            }
+
+            CommandLineResult::Submit(line) => {
+                self.command_line = None;
+
+                if line.trim().is_empty() {
+                    return None;
+                }
+
+                if self.command_history.last() != Some(&line) {
+                    self.command_history.push(line.clone());
+
+                    if self.command_history.len() > COMMAND_HISTORY_LENGTH {
+                        self.command_history.remove(0);
+                    }
+                }
 
-        if running_changed {
-            if self.running {
-                app_command = Some(AppCommand::TimerStart(Duration::from_millis(20)));
-            } else {
-                app_command = Some(AppCommand::TimerStop);
+                let old_running = self.running;
+
+                let result =
+                    Command::parse(&line).and_then(|command| self.run_command(command, life));
+
+                match result {
+                    Ok(app_command) => app_command.or(self.running_command(old_running)),
+                    Err(err) => {
+                        self.message = Some(err.to_string());
+                        self.running_command(old_running)
+                    }
+                }
             }
         }
+    }
 
-        app_command
+    /// Run a command from the command line.
+    ///
+    /// Commands that only show a setting put it in the status bar. Commands change the grid and
+    /// this window directly, like the keys that do the same things, since [`AppCommand`] is only
+    /// for what the main loop owns: the timer, the cursor, and quitting. Those are returned.
+    fn run_command(
+        &mut self,
+        command: Command,
+        life: &mut Life,
+    ) -> crate::Result<Option<AppCommand>> {
+        match command {
+            Command::Rule(Some(rule)) => life.set_rule(rule),
+
+            Command::Rule(None) => self.message = Some(format!("Rule {}", life.rule())),
+
+            Command::Load(path) => self.load(life, &path)?,
+
+            Command::Save(path) => self.save(life, path.as_deref()),
+
+            Command::Gen(generations) => {
+                self.running = false;
+                self.generations_left = generations;
+
+                // Step on ticks as fast as possible, so the screen keeps up and keys get through
+                return Ok(Some(AppCommand::TimerStart(Duration::ZERO)));
+            }
+
+            Command::Speed(Some(speed)) => {
+                self.speed = speed;
+
+                // Restart the timer at the new speed
+                if self.running {
+                    return Ok(Some(AppCommand::TimerStart(self.tick_rate())));
+                }
+            }
+
+            Command::Speed(None) => {
+                self.message = Some(format!("Speed {} generations/s", self.speed));
+            }
+
+            Command::Topology(Some(topology)) => life.set_topology(topology),
+
+            Command::Topology(None) => {
+                self.message = Some(format!("Topology {}", life.topology().name()));
+            }
+
+            Command::Seed(Some(seed)) => self.randomize(life, Some(seed)),
+
+            Command::Seed(None) => {
+                self.message = Some(match self.soup_origin {
+                    Some(origin) => format!("Seed {}", origin.seed),
+                    None => "No soup seed".to_string(),
+                });
+            }
+
+            Command::Quit => return Ok(Some(AppCommand::Quit)),
+        }
+
+        Ok(None)
+    }
+
+    /// Handle keys that do something different when selecting or pasting.
+    ///
+    /// Returns `true` if the key was used.
+    fn handle_mode_key(&mut self, key_event: &KeyEvent, life: &mut Life) -> bool {
+        let actions: &[Action] = match self.mode {
+            Mode::Normal => return false,
+            Mode::Select { .. } => &SELECT_ACTIONS,
+            Mode::Paste(_) => &PASTE_ACTIONS,
+            Mode::Draw { .. } => &DRAW_ACTIONS,
+            Mode::Fill => &FILL_ACTIONS,
+        };
+
+        let Some(action) = self.config.keymap.action(key_event, actions) else {
+            return false;
+        };
+
+        match (self.mode, action) {
+            // The key that started a mode also ends it, except paste where it places
+            (_, Action::Cancel)
+            | (Mode::Select { .. }, Action::Select)
+            | (Mode::Draw { .. }, Action::Draw)
+            | (Mode::Fill, Action::Fill) => {
+                self.mode = Mode::Normal;
+            }
+
+            (
+                Mode::Select { .. },
+                Action::Copy | Action::Cut | Action::ClearSelection | Action::ClearOutside,
+            ) => {
+                let (x, y, width, height) = self.selection_rect(life).unwrap();
+
+                if action == Action::Copy || action == Action::Cut {
+                    let pattern = life.copy_region(x, y, width, height);
+
+                    // Not every terminal supports this, so there's nothing to do if it fails
+                    util::set_clipboard(&pattern.to_rle(&life.rule(), &[])).ok();
+                    self.clipboard = Some(pattern);
+                }
+
+                match action {
+                    Action::Cut | Action::ClearSelection => {
+                        self.running = false;
+                        life.fill_region(x, y, width, height, LifeCell::Dead);
+                    }
+                    Action::ClearOutside => {
+                        self.running = false;
+                        life.clear_outside(x, y, width, height);
+                    }
+                    _ => (),
+                }
+
+                self.mode = Mode::Normal;
+            }
+
+            // Watch the selection for a period, or the whole grid again if it's just one cell
+            (
+                Mode::Select {
+                    anchor_x, anchor_y, ..
+                },
+                Action::Period,
+            ) => {
+                if (anchor_x, anchor_y) == (self.cursor_x, self.cursor_y) {
+                    life.watch(None);
+                    self.message = Some("Looking for a period in the whole grid".to_string());
+                } else {
+                    life.watch(self.selection_rect(life));
+                    self.message = Some("Looking for a period in the selection".to_string());
+                }
+
+                self.period = None;
+                self.mode = Mode::Normal;
+            }
+
+            (Mode::Select { .. }, Action::Track) => {
+                match Tracker::new(life, self.selection_rect(life).unwrap()) {
+                    Ok(tracker) => {
+                        self.message =
+                            Some(format!("Tracking spaceship, {}", tracker.description()));
+                        self.tracker = Some(tracker);
+                        self.follow(life);
+                    }
+                    Err(err) => self.message = Some(err.to_string()),
+                }
+
+                self.mode = Mode::Normal;
+            }
+
+            (Mode::Select { .. }, action) if transform_for_action(action).is_some() => {
+                let (x, y, width, height) = self.selection_rect(life).unwrap();
+                let transform = transform_for_action(action).unwrap();
+
+                let Some((width, height)) = life.transform_region(x, y, width, height, transform)
+                else {
+                    self.message = Some("No room to transform without covering cells".to_string());
+                    return true;
+                };
+
+                self.running = false;
+
+                // Keep the selection covering the transformed region
+                self.mode = Mode::Select {
+                    anchor_x: x,
+                    anchor_y: y,
+                    transformed: Some((x, y, width, height)),
+                };
+                self.cursor_x = (x + width - 1) % life.width();
+                self.cursor_y = (y + height - 1) % life.height();
+            }
+
+            (Mode::Paste(_), action) if transform_for_action(action).is_some() => {
+                let transform = transform_for_action(action).unwrap();
+
+                if let Some(clipboard) = &self.clipboard {
+                    self.clipboard = Some(clipboard.transformed(transform));
+                }
+            }
+
+            (Mode::Paste(mode), Action::PasteMode) => {
+                self.mode = Mode::Paste(mode.next());
+            }
+
+            (Mode::Paste(mode), Action::Place | Action::Paste) => {
+                if let Some(clipboard) = &self.clipboard {
+                    self.running = false;
+                    life.paste(clipboard, self.cursor_x, self.cursor_y, mode);
+                }
+
+                self.mode = Mode::Normal;
+            }
+
+            (
+                Mode::Draw {
+                    anchor_x,
+                    anchor_y,
+                    shape,
+                },
+                Action::NextShape,
+            ) => {
+                self.mode = Mode::Draw {
+                    anchor_x,
+                    anchor_y,
+                    shape: shape.next(),
+                };
+            }
+
+            (Mode::Draw { .. } | Mode::Fill, Action::Place) => {
+                if let Some((pattern, x, y, mode)) = self.drawn_pattern(life) {
+                    self.running = false;
+                    life.paste(&pattern, x, y, mode);
+                }
+
+                self.mode = Mode::Normal;
+            }
+
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Get the pattern the current drawing tool would paste, along with where and how to paste
+    /// it, if drawing or filling.
+    fn drawn_pattern(&self, life: &Life) -> Option<(Pattern, usize, usize, PasteMode)> {
+        match self.mode {
+            Mode::Draw {
+                anchor_x,
+                anchor_y,
+                shape,
+            } => {
+                let dx = wrapped_delta(anchor_x, self.cursor_x, life.width());
+                let dy = wrapped_delta(anchor_y, self.cursor_y, life.height());
+                let (pattern, left, top) = shape.pattern(dx, dy);
+
+                let x = (anchor_x as isize + left).rem_euclid(life.width() as isize) as usize;
+                let y = (anchor_y as isize + top).rem_euclid(life.height() as isize) as usize;
+
+                Some((pattern, x, y, PasteMode::Or))
+            }
+
+            Mode::Fill => Some((
+                life.flood_region(self.cursor_x, self.cursor_y),
+                0,
+                0,
+                PasteMode::Xor,
+            )),
+
+            _ => None,
+        }
+    }
+
+    /// Get the selected rectangle as `(x, y, width, height)`, if selecting.
+    ///
+    /// The selection goes the short way around the universe from the anchor to the cursor.
+    fn selection_rect(&self, life: &Life) -> Option<(usize, usize, usize, usize)> {
+        let Mode::Select {
+            anchor_x,
+            anchor_y,
+            transformed,
+        } = self.mode
+        else {
+            return None;
+        };
+
+        if let Some((x, y, width, height)) = transformed
+            && self.cursor_x == (x + width - 1) % life.width()
+            && self.cursor_y == (y + height - 1) % life.height()
+        {
+            return Some((x, y, width, height));
+        }
+
+        let (x, width) = wrapped_span(anchor_x, self.cursor_x, life.width());
+        let (y, height) = wrapped_span(anchor_y, self.cursor_y, life.height());
+
+        Some((x, y, width, height))
+    }
+
+    /// Handle Life window mouse events.
+    ///
+    /// Left button paints, right button erases, middle button pans, and the wheel zooms.
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: &MouseEvent,
+        life: &mut Life,
+    ) -> Option<AppCommand> {
+        // The command line has the keyboard, so the grid shouldn't change under it
+        if self.command_line.is_some() {
+            return None;
+        }
+
+        let old_running = self.running;
+        let (column, row) = (mouse_event.column, mouse_event.row);
+
+        match mouse_event.kind {
+            MouseEventKind::Down(button) | MouseEventKind::Drag(button)
+                if button != MouseButton::Middle =>
+            {
+                let Some((dx, dy)) = self.viewport.screen_to_offset(column, row, life) else {
+                    self.mouse_paint = None;
+                    return None;
+                };
+
+                let x = (self.viewport.x + dx) as isize;
+                let y = (self.viewport.y + dy) as isize;
+
+                let state = if button == MouseButton::Left {
+                    LifeCell::Alive
+                } else {
+                    LifeCell::Dead
+                };
+
+                self.running = false;
+
+                // Fill in the gaps if the mouse moved more than one cell
+                match (mouse_event.kind, self.mouse_paint) {
+                    (MouseEventKind::Drag(_), Some((x0, y0))) => life.line(x0, y0, x, y, state),
+                    _ => life.set_cell_wrapped(x, y, state),
+                }
+
+                self.mouse_paint = Some((x, y));
+                self.cursor_x = x as usize % life.width();
+                self.cursor_y = y as usize % life.height();
+            }
+
+            MouseEventKind::Down(MouseButton::Middle) => {
+                self.mouse_pan = Some((column, row));
+            }
+
+            MouseEventKind::Drag(MouseButton::Middle) => {
+                if let Some((last_column, last_row)) = self.mouse_pan {
+                    let (cx, cy) = self.viewport.zoom().cells_per_char();
+                    let (chw, chh) = self.viewport.zoom().chars_per_cell();
+
+                    // When zoomed in, wait until the mouse has moved a whole cell
+                    let cells_x =
+                        (column as isize - last_column as isize) / chw as isize * cx as isize;
+                    let cells_y = (row as isize - last_row as isize) / chh as isize * cy as isize;
+
+                    self.mouse_pan = Some((
+                        (last_column as isize + cells_x / cx as isize * chw as isize) as u16,
+                        (last_row as isize + cells_y / cy as isize * chh as isize) as u16,
+                    ));
+
+                    self.viewport.pan(-cells_x, -cells_y, life);
+                    self.move_cursor(-cells_x, -cells_y, life);
+                }
+            }
+
+            MouseEventKind::Up(_) => {
+                self.mouse_paint = None;
+                self.mouse_pan = None;
+            }
+
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let anchor = self.viewport.screen_to_cell(column, row, life);
+
+                let zoomed = if mouse_event.kind == MouseEventKind::ScrollUp {
+                    self.viewport.zoom_in()
+                } else {
+                    self.viewport.zoom_out()
+                };
+
+                // Keep the cell under the mouse in place, and bring the cursor along
+                if zoomed {
+                    if let Some((x, y)) = anchor {
+                        self.cursor_x = x;
+                        self.cursor_y = y;
+                    }
+
+                    self.zoom_changed(life);
+
+                    if anchor.is_some() {
+                        self.viewport
+                            .anchor(self.cursor_x, self.cursor_y, column, row, life);
+                    }
+                }
+            }
+
+            _ => (),
+        }
+
+        self.running_command(old_running)
+    }
+
+    /// Get the timer command needed if the running state changed.
+    fn running_command(&self, old_running: bool) -> Option<AppCommand> {
+        if old_running == self.running {
+            None
+        } else if self.running {
+            Some(AppCommand::TimerStart(self.tick_rate()))
+        } else {
+            Some(AppCommand::TimerStop)
+        }
+    }
+
+    /// Switch to newly saved settings.
+    ///
+    /// Returns a command to restart the timer at the new speed if running.
+    ///
+    /// The rule and topology are only defaults for the next start, so the universe keeps the ones
+    /// it has, which may have come from `:rule` or a loaded file.
+    fn apply_config(&mut self, config: Config) -> Option<AppCommand> {
+        self.speed = config.speed;
+        self.soup = config.soup;
+        self.theme = config.appearance.theme(config.appearance.theme);
+        self.viewport.gridlines = config.appearance.gridlines;
+        self.config = config;
+
+        self.running
+            .then(|| AppCommand::TimerStart(self.tick_rate()))
+    }
+
+    /// Look for a period after stepping, and say so when the cells start repeating.
+    fn check_period(&mut self, life: &Life) {
+        let period = life.period();
+
+        if let Some(period) = period
+            && self.period.is_none()
+        {
+            self.message = Some(self.describe_period(life, period));
+        }
+
+        self.period = period;
+    }
+
+    /// Describe how the cells repeat, and which cells if it's not the whole grid.
+    fn describe_period(&self, life: &Life, period: Period) -> String {
+        match life.watched() {
+            Some(_) => format!("Selection: {period}"),
+            None => period.to_string(),
+        }
+    }
+
+    /// Keep the spaceship being tracked in the middle of the view, with the cursor on it.
+    fn follow(&mut self, life: &Life) {
+        let Some(tracker) = &self.tracker else {
+            return;
+        };
+
+        match tracker.follow(life) {
+            Some((x, y)) => {
+                self.cursor_x = x;
+                self.cursor_y = y;
+                self.viewport.center_on(x, y, life);
+            }
+            None => {
+                self.tracker = None;
+                self.message = Some("Lost track of the spaceship".to_string());
+            }
+        }
+    }
+
+    /// Step the generations left from `:gen` for up to [`GEN_CHUNK_TIME`].
+    ///
+    /// Returns a command to stop the timer once they're all done.
+    fn step_generations_left(&mut self, life: &mut Life) -> Option<AppCommand> {
+        let started = Instant::now();
+
+        while self.generations_left > 0 && started.elapsed() < GEN_CHUNK_TIME {
+            life.step();
+            self.generations_left -= 1;
+        }
+
+        self.check_period(life);
+        self.follow(life);
+
+        (self.generations_left == 0).then_some(AppCommand::TimerStop)
+    }
+
+    /// Stop running, and stop any `:gen` partway.
+    pub fn pause(&mut self) {
+        self.running = false;
+        self.generations_left = 0;
+    }
+
+    /// Get the time between generations when running.
+    fn tick_rate(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.speed as f64)
+    }
+
+    /// Move the cursor, wrapping around the universe and scrolling it into view.
+    fn move_cursor(&mut self, dx: isize, dy: isize, life: &Life) {
+        if life.width() == 0 || life.height() == 0 {
+            return;
+        }
+
+        self.cursor_x = (self.cursor_x as isize + dx).rem_euclid(life.width() as isize) as usize;
+        self.cursor_y = (self.cursor_y as isize + dy).rem_euclid(life.height() as isize) as usize;
+
+        self.viewport.scroll_to(self.cursor_x, self.cursor_y, life);
+    }
+
+    /// Pan the view by a quarter screen in the given directions.
+    ///
+    /// The cursor moves with the view so it stays in the same place on screen.
+    fn pan(&mut self, dx: isize, dy: isize, life: &Life) {
+        let (vis_w, vis_h) = self.viewport.visible_cells(life);
+        let dx = dx * (vis_w as isize / 4).max(1);
+        let dy = dy * (vis_h as isize / 4).max(1);
+
+        self.viewport.pan(dx, dy, life);
+        self.move_cursor(dx, dy, life);
+    }
+
+    /// Fit the universe to the view after zooming, and recenter on the cursor.
+    fn zoom_changed(&mut self, life: &mut Life) {
+        self.fit_universe(life);
+        self.viewport.center_on(self.cursor_x, self.cursor_y, life);
+    }
+
+    /// Get the status bar hints for quitting and help, with whatever keys they're bound to.
+    fn key_hints(&self) -> String {
+        [(Action::Quit, "Quit"), (Action::Help, "Help")]
+            .iter()
+            .filter_map(|(action, name)| {
+                let key = self.config.keymap.keys(*action).first()?;
+                Some(format!("{key}→{name}"))
+            })
+            .fold(String::new(), |hints, hint| {
+                if hints.is_empty() {
+                    format!(" {hint} ")
+                } else {
+                    format!("{hints}| {hint} ")
+                }
+            })
+    }
+
+    /// Get the status bar text for the current mode.
+    ///
+    /// `drawn` is the pattern from [`LifeWindow::drawn_pattern`], if drawing or filling.
+    fn mode_status(&self, life: &Life, drawn: Option<&Pattern>) -> String {
+        match self.mode {
+            Mode::Normal => match &self.message {
+                Some(message) => format!(" {message} "),
+                None => String::new(),
+            },
+            Mode::Select { .. } => match &self.message {
+                Some(message) => format!(" {message} "),
+                None => {
+                    let (_, _, width, height) = self.selection_rect(life).unwrap();
+                    format!(" SELECT {width}x{height} ")
+                }
+            },
+            Mode::Paste(mode) => format!(" PASTE {} ", mode.name()),
+            Mode::Draw { shape, .. } => {
+                let pattern = drawn.unwrap();
+                format!(
+                    " DRAW {} {}x{} ",
+                    shape.name(),
+                    pattern.width(),
+                    pattern.height()
+                )
+            }
+            Mode::Fill => format!(" FILL {} ", drawn.unwrap().population()),
+        }
     }
 
-    /// Initialize the LifeWindow.
-    pub fn init(&mut self) {
-        let terminal_size = util::get_terminal_size();
+    /// Fill the selection, or the whole grid if there isn't one, with a random soup.
+    ///
+    /// If there's no seed, a new one is picked. Random seeds are kept small so they're easy to
+    /// type back in.
+    ///
+    /// Without a selection, a seed given again makes the same size soup as last time, in the
+    /// middle of an otherwise empty grid, so it comes out the same.
+    fn randomize(&mut self, life: &mut Life, seed: Option<u64>) {
+        let selection = self.selection_rect(life);
 
-        self.cursor_x = terminal_size.width / 2;
-        self.cursor_y = terminal_size.height / 2;
+        let (x, y, width, height) = match (selection, seed.and(self.soup_origin)) {
+            (Some(rect), _) => rect,
+            (None, Some(origin)) => {
+                let width = origin.width.min(life.width());
+                let height = origin.height.min(life.height());
+
+                (
+                    (life.width() - width) / 2,
+                    (life.height() - height) / 2,
+                    width,
+                    height,
+                )
+            }
+            (None, None) => (0, 0, life.width(), life.height()),
+        };
+
+        // The grid is only this soup if there's nothing else on it
+        let alone = match selection {
+            Some(_) => life.population() == life.copy_region(x, y, width, height).population(),
+            None => {
+                life.fill_region(0, 0, life.width(), life.height(), LifeCell::Dead);
+                true
+            }
+        };
+
+        let seed = seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+
+        life.randomize(x, y, width, height, &self.soup, seed);
+
+        self.soup_origin = alone.then_some(SoupOrigin {
+            soup: self.soup,
+            seed,
+            width,
+            height,
+        });
+
+        // A soup over the whole grid is a fresh start
+        match self.mode {
+            Mode::Select { .. } => self.mode = Mode::Normal,
+            _ => life.set_generation(0),
+        }
+
+        self.soup_made = (life.generation(), life.edits());
+    }
+
+    /// Forget how the soup was made once the grid's been edited, since it isn't that soup anymore.
+    fn forget_edited_soup(&mut self, life: &Life) {
+        if life.edits() != self.soup_made.1 {
+            self.soup_origin = None;
+        }
+    }
+
+    /// Get the screen area the grid is drawn in, inside the border.
+    fn grid_area(area: Rect) -> Rect {
+        Block::bordered().inner(area)
+    }
+
+    /// Fit the universe to a new terminal size.
+    ///
+    /// The live cells are kept centered, and the universe is never made smaller than them, so if
+    /// the terminal gets too small for the pattern, the view pans around it instead.
+    fn resize(&mut self, width: u16, height: u16, life: &mut Life) {
+        self.viewport.area = Self::grid_area(Rect::new(0, 0, width, height));
+        self.fit_universe(life);
+
+        // The pattern is centered in the universe now, so centering the universe centers it
+        self.viewport
+            .center_on(life.width() / 2, life.height() / 2, life);
+    }
+
+    /// Get the size the universe should be to fill the view.
+    ///
+    /// It's never smaller than one cell per character, so zooming in doesn't squeeze the pattern
+    /// into a tiny torus, and never bigger than a pattern that can be read back from the session.
+    fn universe_size(&self) -> (usize, usize) {
+        let (area_w, area_h) = self.viewport.area_cells();
+
+        (
+            area_w.max(self.viewport.area.width as usize).min(MAX_SIZE),
+            area_h.max(self.viewport.area.height as usize).min(MAX_SIZE),
+        )
+    }
+
+    /// Resize the universe to fill the view, keeping the live cells centered and moving the
+    /// cursor and anything else on a cell along with them.
+    fn fit_universe(&mut self, life: &mut Life) {
+        let (area_w, area_h) = self.universe_size();
+        let (_, _, bw, bh) = life.bounding_box().unwrap_or_default();
+
+        // Already the size it would be, so leave the cells and their history alone
+        if (life.width(), life.height()) == (area_w.max(bw), area_h.max(bh)) {
+            return;
+        }
+
+        let tracked = self
+            .tracker
+            .as_ref()
+            .and_then(|tracker| tracker.corner(life));
+        let moved = life.resize_centered(area_w, area_h);
+
+        (self.cursor_x, self.cursor_y) = moved(self.cursor_x, self.cursor_y);
+
+        if let (Some(tracker), Some((x, y))) = (self.tracker.as_mut(), tracked) {
+            tracker.moved_to(life, moved(x, y));
+        }
+
+        if let Mode::Select {
+            anchor_x, anchor_y, ..
+        }
+        | Mode::Draw {
+            anchor_x, anchor_y, ..
+        } = &mut self.mode
+        {
+            (*anchor_x, *anchor_y) = moved(*anchor_x, *anchor_y);
+        }
+
+        if let Mode::Select {
+            transformed: Some((x, y, ..)),
+            ..
+        } = &mut self.mode
+        {
+            (*x, *y) = moved(*x, *y);
+        }
+
+        self.mouse_paint = None;
+    }
+
+    /// Initialize the LifeWindow for a terminal size and start with a saved session, or a random
+    /// soup if there isn't one.
+    pub fn init(&mut self, size: Size, life: &mut Life, session: Option<Session>) {
+        let area = Self::grid_area(Rect::new(0, 0, size.width, size.height));
+
+        if let Some(session) = session {
+            self.restore(size, life, session);
+            return;
+        }
+
+        life.init(area.width as usize, area.height as usize);
+
+        self.cursor_x = life.width() / 2;
+        self.cursor_y = life.height() / 2;
+
+        life.set_rule(self.config.rule);
+        life.set_topology(self.config.topology);
+
+        self.randomize(life, None);
+    }
+
+    /// Carry on from a saved session.
+    ///
+    /// If the terminal is bigger than the universe now, the universe is fit to it like after a
+    /// resize. Otherwise it's kept as it was saved, along with the view of it.
+    fn restore(&mut self, size: Size, life: &mut Life, session: Session) {
+        let pattern = &session.pattern;
+
+        life.init(pattern.width(), pattern.height());
+        life.paste(pattern, 0, 0, PasteMode::Copy);
+        life.set_rule(session.rule);
+        life.set_topology(session.topology);
+        life.set_generation(session.generation);
+
+        self.speed = session.speed.clamp(1, MAX_SPEED);
+        self.viewport.set_zoom_level(session.zoom);
+        self.viewport.area = Self::grid_area(Rect::new(0, 0, size.width, size.height));
+        self.cursor_x = session.cursor.0.min(life.width().saturating_sub(1));
+        self.cursor_y = session.cursor.1.min(life.height().saturating_sub(1));
+
+        let (area_w, area_h) = self.universe_size();
+
+        if life.width() >= area_w && life.height() >= area_h {
+            self.viewport.x = session.viewport.0 % life.width();
+            self.viewport.y = session.viewport.1 % life.height();
+        } else {
+            self.resize(size.width, size.height, life);
+        }
+    }
+
+    /// Get everything needed to carry on from here next time, if sessions are being saved.
+    pub fn session(&self, life: &Life) -> Option<Session> {
+        if !self.config.save_session {
+            return None;
+        }
+
+        Some(Session {
+            pattern: life.copy_region(0, 0, life.width(), life.height()),
+            rule: life.rule(),
+            topology: life.topology(),
+            generation: life.generation(),
+            speed: self.speed,
+            cursor: (self.cursor_x, self.cursor_y),
+            viewport: (self.viewport.x, self.viewport.y),
+            zoom: self.viewport.zoom_level(),
+        })
+    }
+
+    /// Replace the grid with a pattern from an RLE or plaintext file, centered.
+    ///
+    /// The universe grows if the pattern doesn't fit, and the rule changes if the file has one.
+    fn load(&mut self, life: &mut Life, path: &str) -> crate::Result<()> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Can't load {path}: {err}"))?;
+        let pattern =
+            Pattern::from_text(&text).map_err(|err| format!("Can't load {path}: {err}"))?;
+
+        if let Some(rule) = Pattern::rle_rule(&text) {
+            life.set_rule(rule?);
+        }
+
+        let (area_w, area_h) = self.universe_size();
+        let width = area_w.max(pattern.width());
+        let height = area_h.max(pattern.height());
+
+        life.init(width, height);
+        life.paste(
+            &pattern,
+            (width - pattern.width()) / 2,
+            (height - pattern.height()) / 2,
+            PasteMode::Copy,
+        );
+
+        // The history starts with the pattern in place
+        life.set_generation(0);
+
+        // Whatever was being tracked or repeating is gone
+        self.tracker = None;
+        self.period = None;
+        self.mode = Mode::Normal;
+        self.soup_origin = None;
+        self.cursor_x = width / 2;
+        self.cursor_y = height / 2;
+        self.viewport.center_on(self.cursor_x, self.cursor_y, life);
+
+        self.message = Some(format!(
+            "Loaded {path}, {}x{}",
+            pattern.width(),
+            pattern.height()
+        ));
+
+        Ok(())
+    }
+
+    /// Save the pattern as RLE, to a new file in the current directory if there's no path.
+    ///
+    /// If the grid started as a random soup, the soup's seed and settings go in a comment.
+    fn save(&mut self, life: &Life, path: Option<&str>) {
+        let Some((x, y, width, height)) = life.bounding_box() else {
+            self.message = Some("Nothing to save".to_string());
+            return;
+        };
+
+        let comments: Vec<String> = self
+            .soup_origin
+            .iter()
+            .map(
+                |origin| match life.generation().saturating_sub(self.soup_made.0) {
+                    0 => origin.comment(),
+                    generations => format!("{}, after {generations} generations", origin.comment()),
+                },
+            )
+            .collect();
+        let rle = life
+            .copy_region(x, y, width, height)
+            .to_rle(&life.rule(), &comments);
+
+        let path = path.map_or_else(
+            || util::timestamped_file_name("rustlife", "rle"),
+            str::to_string,
+        );
+
+        self.message = Some(match fs::write(&path, rle) {
+            Ok(()) => format!("Saved {path}"),
+            Err(err) => format!("Can't save {path}: {err}"),
+        });
     }
 
     /// Draw the LifeWindow.
@@ -183,27 +1487,127 @@ impl LifeWindow {
         frame: &mut ratatui::Frame,
         life: &mut Life,
     ) -> Option<WindowDrawResult> {
+        // The universe might have changed size since the last draw
+        self.cursor_x = self.cursor_x.min(life.width().saturating_sub(1));
+        self.cursor_y = self.cursor_y.min(life.height().saturating_sub(1));
+
+        // Shapes and fills are worked out fresh since the grid might have changed underneath
+        let drawn = self.drawn_pattern(life);
+
+        // Births and deaths would be misleading with a pattern floating over the grid
+        let changes = (self.next_generation
+            && !self.running
+            && matches!(self.mode, Mode::Normal | Mode::Select { .. }))
+        .then(|| life.next_changes());
+
         let block = Block::bordered()
+            .title(Line::from(format!(
+                " Gen {} Pop {} {} ",
+                life.generation(),
+                life.population(),
+                util::sparkline(
+                    life.history()
+                        .iter()
+                        .rev()
+                        .take(SPARKLINE_LENGTH)
+                        .rev()
+                        .map(|stats| stats.population)
+                )
+            )))
             .title(Line::from(" Life ".bold()).centered())
-            .title_bottom(Line::from(" q→Quit | ?→Help ").centered())
-            .border_set(border::THICK);
+            .title(
+                Line::from(match self.soup_origin {
+                    Some(origin) => format!(" Seed {} ", origin.seed),
+                    None => String::new(),
+                })
+                .right_aligned(),
+            )
+            .title_bottom(Line::from(format!(
+                " {},{} {} {}",
+                self.viewport.x,
+                self.viewport.y,
+                self.viewport.zoom().label(),
+                if self.tracker.is_some() {
+                    "Tracking "
+                } else {
+                    ""
+                }
+            )))
+            .title_bottom(Line::from(self.key_hints()).centered())
+            .title_bottom(
+                Line::from(self.mode_status(life, drawn.as_ref().map(|(pattern, ..)| pattern)))
+                    .right_aligned(),
+            )
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(self.theme.border));
 
-        let life_widget = LifeWidget::new(life).block(block);
+        self.viewport.area = Self::grid_area(frame.area());
 
-        let inner = life_widget.inner(frame.area());
+        // The screen might have changed size since the last draw
+        self.viewport.pan(0, 0, life);
+        self.viewport.scroll_to(self.cursor_x, self.cursor_y, life);
+
+        let life_widget = LifeWidget::new(life)
+            .block(block)
+            .viewport(&self.viewport)
+            .coloring(self.coloring)
+            .theme(&self.theme)
+            .cursor(self.cursor_x, self.cursor_y)
+            .selection(self.selection_rect(life))
+            .info(self.info)
+            .changes(changes.as_ref())
+            .preview(match (self.mode, &self.clipboard) {
+                (Mode::Paste(mode), Some(pattern)) => Some(Preview {
+                    pattern,
+                    x: self.cursor_x,
+                    y: self.cursor_y,
+                    mode,
+                    cells_only: false,
+                }),
+                _ => drawn.as_ref().map(|(pattern, x, y, mode)| Preview {
+                    pattern,
+                    x: *x,
+                    y: *y,
+                    mode: *mode,
+                    cells_only: true,
+                }),
+            });
 
         frame.render_widget(life_widget, frame.area());
 
-        (self.cursor_x, self.cursor_y) = util::clamp_to_rect(self.cursor_x, self.cursor_y, inner);
+        // The command line covers the bottom border, like vi's
+        if let Some(command_line) = &self.command_line {
+            let area = frame.area();
+            let line_area = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+
+            // Show the end of the line if it's too long to fit
+            let text = format!(":{}", command_line.text());
+            let skip = text
+                .chars()
+                .count()
+                .saturating_sub(line_area.width.saturating_sub(1) as usize);
+            let text: String = text.chars().skip(skip).collect();
+
+            frame.render_widget(Clear, line_area);
+            frame.render_widget(Paragraph::new(text.as_str()), line_area);
+
+            return Some(WindowDrawResult::cursor_position(
+                line_area.x + text.chars().count() as u16,
+                line_area.y,
+            ));
+        }
 
         if let Some(win) = self.child_window.as_mut() {
-            return win.draw(frame);
+            return win.draw(frame, &self.theme, life);
         }
 
-        Some(WindowDrawResult::cursor_position(
-            self.cursor_x,
-            self.cursor_y,
-        ))
+        match self
+            .viewport
+            .cell_to_screen(self.cursor_x, self.cursor_y, life)
+        {
+            Some((x, y)) => Some(WindowDrawResult::cursor_position(x, y)),
+            None => Some(WindowDrawResult::cursor_hide()),
+        }
     }
 
     /// Handle application events.
@@ -215,16 +1619,40 @@ impl LifeWindow {
         let mut app_command = None;
 
         if let Some(win) = self.child_window.as_mut() {
-            let result = win.handle_app_event(app_event);
+            let result = win.handle_app_event(app_event, life, &self.config.keymap);
 
             if let Some(command) = result
                 && command == AppCommand::CloseChildWindow
             {
-                self.child_window = None;
+                match self.child_window.take() {
+                    // Stamp whatever was picked from the library at the cursor
+                    Some(LifeChildWindow::Library(mut win)) => {
+                        if let Some(pattern) = win.take_chosen() {
+                            life.paste(&pattern, self.cursor_x, self.cursor_y, PasteMode::Or);
+                        }
+                    }
+
+                    Some(LifeChildWindow::Soup(mut win)) => {
+                        if let Some((soup, seed)) = win.take_chosen() {
+                            self.soup = soup;
+                            self.randomize(life, seed);
+                        }
+                    }
+
+                    Some(LifeChildWindow::Settings(mut win)) => {
+                        if let Some((config, path)) = win.take_saved() {
+                            app_command = self.apply_config(config);
+                            self.message = Some(format!("Saved settings to {}", path.display()));
+                        }
+                    }
+
+                    _ => (),
+                }
             }
         }
 
         if !app_event.propagate {
+            self.forget_edited_soup(life);
             return app_command;
         }
 
@@ -234,18 +1662,75 @@ impl LifeWindow {
                     app_command = self.handle_key_event(key_event, life);
                 }
 
+                Event::Mouse(mouse_event) => {
+                    app_command = self.handle_mouse_event(mouse_event, life);
+                }
+
+                // Text pasted while typing a command goes into the command
+                Event::Paste(text) => match &mut self.command_line {
+                    Some(command_line) => command_line.paste(text),
+
+                    None => match Pattern::from_text(text) {
+                        Ok(pattern) => {
+                            self.clipboard = Some(pattern);
+                            self.mode = Mode::Paste(PasteMode::Or);
+                        }
+
+                        Err(err) => self.message = Some(format!("Can't paste: {err}")),
+                    },
+                },
+
                 Event::Resize(width, height) => {
-                    life.resize(*width as usize - 1, *height as usize - 2);
+                    self.resize(*width, *height, life);
                 }
 
                 _ => (),
             },
 
+            AppEventType::Tick if self.generations_left > 0 => {
+                app_command = self.step_generations_left(life);
+            }
+
             AppEventType::Tick => {
                 life.step();
+                self.check_period(life);
+                self.follow(life);
             }
         }
 
+        self.forget_edited_soup(life);
+
         app_command
     }
 }
+
+/// Get the distance the short way around a wrapping axis from one point to another.
+fn wrapped_delta(a: usize, b: usize, size: usize) -> isize {
+    let forward = (b + size - a) % size.max(1);
+
+    if forward <= size / 2 {
+        forward as isize
+    } else {
+        forward as isize - size as isize
+    }
+}
+
+/// Get the start and length of the short way around a wrapping axis between two points.
+fn wrapped_span(a: usize, b: usize, size: usize) -> (usize, usize) {
+    let delta = wrapped_delta(a, b, size);
+
+    (if delta < 0 { b } else { a }, delta.unsigned_abs() + 1)
+}
+
+/// Get the transform for an action used in select or paste mode.
+fn transform_for_action(action: Action) -> Option<Transform> {
+    match action {
+        Action::RotateCw => Some(Transform::RotateCw),
+        Action::RotateCcw => Some(Transform::RotateCcw),
+        Action::Rotate180 => Some(Transform::Rotate180),
+        Action::FlipHorizontal => Some(Transform::FlipHorizontal),
+        Action::FlipVertical => Some(Transform::FlipVertical),
+        Action::Transpose => Some(Transform::Transpose),
+        _ => None,
+    }
+}
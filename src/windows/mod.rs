@@ -1,13 +1,43 @@
 mod about;
+mod census;
+mod graph;
 mod help;
+mod library;
 mod life;
 mod root;
+mod settings;
+mod soup;
 mod toosmall;
 mod window;
 
+use crate::keymap::Action;
+
 pub use about::AboutWindow;
+pub use census::CensusWindow;
+pub use graph::GraphWindow;
 pub use help::HelpWindow;
+pub use library::LibraryWindow;
 pub use life::LifeWindow;
-pub use root::RootWindow;
+pub use root::{MIN_SIZE, RootWindow};
+pub use settings::SettingsWindow;
+pub use soup::SoupWindow;
 pub use toosmall::TooSmallWindow;
 pub use window::WindowDrawResult;
+
+/// The actions looked up together for a key, and where, for finding keys bound twice.
+///
+/// A mode's actions take priority over the normal ones, so the same key doing something in both
+/// isn't a clash.
+pub const KEY_CONTEXTS: [(&str, &[Action]); 11] = [
+    ("normal mode", &life::NORMAL_ACTIONS),
+    ("select mode", &life::SELECT_ACTIONS),
+    ("paste mode", &life::PASTE_ACTIONS),
+    ("draw mode", &life::DRAW_ACTIONS),
+    ("fill mode", &life::FILL_ACTIONS),
+    ("the help", &help::ACTIONS),
+    ("the library", &library::ACTIONS),
+    ("the soup window", &soup::ACTIONS),
+    ("the settings", &settings::ACTIONS),
+    ("the graph", &graph::ACTIONS),
+    ("the census", &census::ACTIONS),
+];
@@ -1,26 +1,29 @@
 use crate::{
     AppCommand, AppEvent, AppEventType,
+    config::Config,
     life::Life,
+    session::Session,
     windows::{LifeWindow, TooSmallWindow, WindowDrawResult},
 };
 use crossterm::event::Event;
 use ratatui::layout::Size;
 
+/// Smallest terminal the Life window is shown in, with room for the status bar and dialogs.
+///
+/// This isn't a setting in the config file, since it's what the layout needs rather than a
+/// preference. Smaller terminals get the too small window instead.
+pub const MIN_SIZE: Size = Size {
+    width: 40,
+    height: 20,
+};
+
 /// Root Child Window Types
 enum RootChildWindow {
-    Life(LifeWindow),
+    Life(Box<LifeWindow>),
     TooSmall(TooSmallWindow),
 }
 
 impl RootChildWindow {
-    /// Initialize root child windows.
-    fn init(&mut self) {
-        match self {
-            RootChildWindow::Life(win) => win.init(),
-            RootChildWindow::TooSmall(_) => (),
-        }
-    }
-
     /// Draw root child windows.
     fn draw(&mut self, frame: &mut ratatui::Frame, life: &mut Life) -> Option<WindowDrawResult> {
         match self {
@@ -53,26 +56,42 @@ pub struct RootWindow {
     /// Root window trampoline
     child_window: Option<RootChildWindow>,
 
+    /// The Life window, put aside while the terminal is too small so it carries on where it
+    /// left off.
+    hidden_life_window: Option<Box<LifeWindow>>,
+
     /// True if we're too small.
     too_small: bool,
+
+    /// Session to carry on from, until the Life window starts.
+    session: Option<Session>,
 }
 
 impl RootWindow {
-    /// Create a new Root window.
-    pub fn new() -> Self {
+    /// Create a new Root window, with settings for the Life window from the config file and a
+    /// saved session to carry on from, if any.
+    pub fn new(config: Config, session: Option<Session>) -> Self {
         Self {
             life: Life::new(),
             child_window: None,
+            hidden_life_window: Some(Box::new(LifeWindow::new(config))),
             too_small: false,
+            session,
         }
     }
 
-    /// Choose the proper root window
+    /// Choose the proper root window, swapping the Life window in and out.
     fn set_root_window(&mut self) {
+        // The timer stops while it's too small, so the Life window stops too
+        if let Some(RootChildWindow::Life(mut win)) = self.child_window.take() {
+            win.pause();
+            self.hidden_life_window = Some(win);
+        }
+
         self.child_window = if self.too_small {
             Some(RootChildWindow::TooSmall(TooSmallWindow::new()))
         } else {
-            Some(RootChildWindow::Life(LifeWindow::new()))
+            self.hidden_life_window.take().map(RootChildWindow::Life)
         };
     }
 
@@ -80,7 +99,7 @@ impl RootWindow {
     ///
     /// Returns true if it just changed.
     fn set_too_small(&mut self, width: u16, height: u16) -> bool {
-        let too_small = width < 40 || height < 20;
+        let too_small = width < MIN_SIZE.width || height < MIN_SIZE.height;
 
         if too_small != self.too_small {
             self.too_small = too_small;
@@ -93,16 +112,24 @@ impl RootWindow {
 
     /// Initialize root window.
     pub fn init(&mut self, size: Size) {
+        if let Some(win) = self.hidden_life_window.as_mut() {
+            win.init(size, &mut self.life, self.session.take());
+        }
+
         self.set_too_small(size.width, size.height);
         self.set_root_window(); // Unconditionally do this
+    }
 
-        self.life
-            .init(size.width as usize - 2, size.height as usize - 2);
-
-        self.life.randomize();
-
-        if let Some(win) = self.child_window.as_mut() {
-            win.init();
+    /// Get everything needed to carry on from here next time, if sessions are being saved.
+    ///
+    /// This works even while the terminal is too small, from the Life window put aside.
+    pub fn session(&self) -> Option<Session> {
+        match self.child_window.as_ref() {
+            Some(RootChildWindow::Life(win)) => win.session(&self.life),
+            _ => self
+                .hidden_life_window
+                .as_ref()
+                .and_then(|win| win.session(&self.life)),
         }
     }
 
@@ -0,0 +1,390 @@
+use crate::{
+    AppCommand, AppEvent, AppEventType,
+    config::Config,
+    keymap::{Action, Keymap},
+    rule::{KNOWN_RULES, Rule, Topology},
+    soup::Symmetry,
+    theme::{Theme, ThemeKind},
+    util,
+    windows::WindowDrawResult,
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::{Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Padding, Paragraph, Wrap},
+};
+use std::path::PathBuf;
+
+/// Actions for keys in the Settings window, as well as Tab, Space, `+`, and `-` to choose and
+/// change settings and Enter to save.
+pub(super) const ACTIONS: [Action; 5] = [
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Quit,
+];
+
+/// Smallest step when changing the density.
+const DENSITY_STEP: f64 = 0.025;
+
+/// Speeds to step through, in generations per second.
+const SPEEDS: [u32; 13] = [1, 2, 5, 10, 15, 20, 30, 50, 60, 100, 200, 500, 1000];
+
+/// Glyphs to step through for live cells, after the theme's own.
+const GLYPHS: [char; 9] = ['▓', '█', '■', '●', '◆', 'O', '#', '*', '@'];
+
+/// Settings that can be changed in the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Setting {
+    Rule,
+    Topology,
+    Speed,
+    Density,
+    Symmetry,
+    Theme,
+    Glyph,
+    Gridlines,
+    Session,
+}
+
+/// Settings in the order they're shown.
+const SETTINGS: [Setting; 9] = [
+    Setting::Rule,
+    Setting::Topology,
+    Setting::Speed,
+    Setting::Density,
+    Setting::Symmetry,
+    Setting::Theme,
+    Setting::Glyph,
+    Setting::Gridlines,
+    Setting::Session,
+];
+
+/// Window to change the settings and save them to the config file.
+pub struct SettingsWindow {
+    /// Settings being edited.
+    config: Config,
+
+    /// Rule being typed, checked when saving.
+    rule: String,
+
+    /// Index into the settings of the highlighted one.
+    selected: usize,
+
+    /// Where the settings were drawn, one per line, to find the one clicked.
+    rows_area: Rect,
+
+    /// Why the last save didn't work.
+    error: Option<String>,
+
+    /// Settings and where they were saved, once they have been.
+    saved: Option<(Config, PathBuf)>,
+}
+
+impl SettingsWindow {
+    /// Create a new Settings window starting from the current settings.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.clone(),
+            rule: config.rule.to_string(),
+            selected: 0,
+            rows_area: Rect::default(),
+            error: None,
+            saved: None,
+        }
+    }
+
+    /// Take the settings that were saved and the file they were saved to, if any.
+    pub fn take_saved(&mut self) -> Option<(Config, PathBuf)> {
+        self.saved.take()
+    }
+
+    /// Draw the Settings Window.
+    pub fn draw(&mut self, frame: &mut ratatui::Frame, theme: &Theme) -> Option<WindowDrawResult> {
+        // Borders, padding, and the note, plus room for the error if there is one
+        let height = SETTINGS.len() as u16 + if self.error.is_some() { 8 } else { 6 };
+        let area = util::centered_area(52, height, frame);
+
+        let block = Block::bordered()
+            .title(Line::from(" Settings ".bold()))
+            .title_bottom(Line::from(" ↑↓→Choose | ←→→Change | Enter→Save ").centered())
+            .padding(Padding::new(2, 2, 1, 1))
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(theme.dialog_border));
+
+        let appearance = &self.config.appearance;
+
+        let mut lines: Vec<Line> = SETTINGS
+            .iter()
+            .enumerate()
+            .map(|(i, setting)| {
+                let (name, value, description) = match setting {
+                    Setting::Rule => ("Rule     ", self.rule.clone(), " (type or change)".into()),
+                    Setting::Topology => (
+                        "Topology ",
+                        self.config.topology.name().to_string(),
+                        String::new(),
+                    ),
+                    Setting::Speed => (
+                        "Speed    ",
+                        self.config.speed.to_string(),
+                        " generations/s".into(),
+                    ),
+                    Setting::Density => (
+                        "Density  ",
+                        format!("{:.1}%", self.config.soup.density * 100.0),
+                        String::new(),
+                    ),
+                    Setting::Symmetry => (
+                        "Symmetry ",
+                        self.config.soup.symmetry.name().to_string(),
+                        format!(" ({})", self.config.soup.symmetry.description()),
+                    ),
+                    Setting::Theme => (
+                        "Theme    ",
+                        appearance.theme.name().to_string(),
+                        String::new(),
+                    ),
+                    Setting::Glyph => match appearance.alive_glyph {
+                        Some(glyph) => ("Glyph    ", glyph.to_string(), String::new()),
+                        None => (
+                            "Glyph    ",
+                            "theme".to_string(),
+                            " (the theme's own)".into(),
+                        ),
+                    },
+                    Setting::Gridlines => (
+                        "Gridlines",
+                        if appearance.gridlines { "on" } else { "off" }.to_string(),
+                        String::new(),
+                    ),
+                    Setting::Session => (
+                        "Session  ",
+                        if self.config.save_session {
+                            "on"
+                        } else {
+                            "off"
+                        }
+                        .to_string(),
+                        " (save on quit)".into(),
+                    ),
+                };
+
+                let value_style = if i == self.selected {
+                    Style::new()
+                        .fg(theme.dialog_key)
+                        .add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::new().fg(theme.dialog_key)
+                };
+
+                Line::from(vec![
+                    Span::raw(format!("{name}  ")),
+                    Span::styled(format!(" {value} "), value_style),
+                    Span::styled(description, Style::new().fg(theme.dialog_dim)),
+                ])
+            })
+            .collect();
+
+        lines.push(Line::default());
+        lines.push(Line::from(
+            "Rule and topology take effect next start".fg(theme.dialog_dim),
+        ));
+
+        if let Some(error) = &self.error {
+            lines.push(Line::default());
+            lines.push(Line::from(error.as_str().fg(theme.dialog_key)));
+        }
+
+        self.rows_area = block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: true }).block(block),
+            area,
+        );
+
+        Some(WindowDrawResult::cursor_hide())
+    }
+
+    /// Change the highlighted setting up or down a step.
+    fn change(&mut self, up: bool) {
+        let config = &mut self.config;
+
+        match SETTINGS[self.selected] {
+            Setting::Rule => {
+                // Step through the well-known rules, starting from the top if it's a custom one
+                let i = KNOWN_RULES
+                    .iter()
+                    .position(|rule| Rule::parse(rule).ok() == Rule::parse(&self.rule).ok());
+
+                self.rule = match i {
+                    Some(i) => cycle(&KNOWN_RULES, KNOWN_RULES[i], up),
+                    None => KNOWN_RULES[0],
+                }
+                .to_string();
+            }
+
+            Setting::Topology => config.topology = cycle(&Topology::ALL, config.topology, up),
+
+            Setting::Speed => {
+                config.speed = if up {
+                    SPEEDS.into_iter().find(|speed| *speed > config.speed)
+                } else {
+                    SPEEDS.into_iter().rev().find(|speed| *speed < config.speed)
+                }
+                .unwrap_or(config.speed);
+            }
+
+            Setting::Density => {
+                let step = if up { DENSITY_STEP } else { -DENSITY_STEP };
+
+                // Round so repeated steps don't drift away from the round numbers
+                let steps = ((config.soup.density + step) / DENSITY_STEP).round();
+                config.soup.density = (steps * DENSITY_STEP).clamp(0.0, 1.0);
+            }
+
+            Setting::Symmetry => {
+                config.soup.symmetry = cycle(&Symmetry::ALL, config.soup.symmetry, up);
+            }
+
+            Setting::Theme => {
+                config.appearance.theme = cycle(&ThemeKind::ALL, config.appearance.theme, up);
+            }
+
+            Setting::Glyph => {
+                // The theme's own glyph comes before the others
+                let glyphs: Vec<Option<char>> = [None]
+                    .into_iter()
+                    .chain(GLYPHS.into_iter().map(Some))
+                    .collect();
+
+                let glyph = config.appearance.alive_glyph;
+                config.appearance.alive_glyph = if glyphs.contains(&glyph) {
+                    cycle(&glyphs, glyph, up)
+                } else {
+                    None
+                };
+            }
+
+            Setting::Gridlines => config.appearance.gridlines = !config.appearance.gridlines,
+
+            Setting::Session => config.save_session = !config.save_session,
+        }
+    }
+
+    /// Save the settings to the config file.
+    ///
+    /// Returns `true` if they were saved.
+    fn save(&mut self) -> bool {
+        let result = Rule::parse(&self.rule).and_then(|rule| {
+            self.config.rule = rule;
+            self.config.save()
+        });
+
+        match result {
+            Ok(path) => {
+                self.saved = Some((self.config.clone(), path));
+                true
+            }
+            Err(err) => {
+                self.error = Some(format!("Can't save: {err}"));
+                false
+            }
+        }
+    }
+
+    /// Handle app events for the Settings Window.
+    pub fn handle_app_event(
+        &mut self,
+        app_event: &mut AppEvent,
+        keymap: &Keymap,
+    ) -> Option<AppCommand> {
+        match &app_event.event_type {
+            AppEventType::Event(e) => match e {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    app_event.propagate = false;
+                    self.error = None;
+
+                    let typing_rule = SETTINGS[self.selected] == Setting::Rule;
+
+                    // Typing a rule comes first, so its letters aren't taken as other keys
+                    match (keymap.action(key_event, &ACTIONS), key_event.code) {
+                        (_, KeyCode::Char(c))
+                            if typing_rule && (c.is_ascii_digit() || "BbSs/".contains(c)) =>
+                        {
+                            self.rule.push(c.to_ascii_uppercase());
+                        }
+
+                        (_, KeyCode::Backspace) if typing_rule => {
+                            self.rule.pop();
+                        }
+
+                        (Some(Action::MoveDown), _) | (_, KeyCode::Tab) => {
+                            self.selected = (self.selected + 1) % SETTINGS.len();
+                        }
+
+                        (Some(Action::MoveUp), _) | (_, KeyCode::BackTab) => {
+                            self.selected = (self.selected + SETTINGS.len() - 1) % SETTINGS.len();
+                        }
+
+                        (Some(Action::MoveRight), _)
+                        | (_, KeyCode::Char('+'))
+                        | (_, KeyCode::Char(' ')) => {
+                            self.change(true);
+                        }
+
+                        (Some(Action::MoveLeft), _) | (_, KeyCode::Char('-')) => {
+                            self.change(false);
+                        }
+
+                        (Some(Action::Quit), _) => {
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        (_, KeyCode::Enter) if self.save() => {
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        _ => (),
+                    }
+
+                    None
+                }
+
+                // Clicking a setting highlights it
+                Event::Mouse(mouse_event) => {
+                    if mouse_event.kind == MouseEventKind::Down(MouseButton::Left)
+                        && let Some(i) =
+                            util::clicked_row(self.rows_area, mouse_event.column, mouse_event.row)
+                        && i < SETTINGS.len()
+                    {
+                        self.selected = i;
+                    }
+
+                    None
+                }
+
+                _ => None,
+            },
+
+            _ => None,
+        }
+    }
+}
+
+/// Get the next or previous value in a list, wrapping around.
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, up: bool) -> T {
+    let i = all.iter().position(|value| *value == current).unwrap_or(0);
+
+    if up {
+        all[(i + 1) % all.len()]
+    } else {
+        all[(i + all.len() - 1) % all.len()]
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    AppCommand, AppEvent, AppEventType,
+    keymap::{Action, Keymap},
+    soup::Soup,
+    theme::Theme,
+    util,
+    windows::WindowDrawResult,
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::{Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Padding, Paragraph},
+};
+
+/// Actions for keys in the Soup window, as well as Tab, `+`, and `-` to choose and change
+/// settings, digits for the seed, and Enter to fill.
+pub(super) const ACTIONS: [Action; 6] = [
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::SoupSettings,
+    Action::Quit,
+];
+
+/// Smallest step when changing the density.
+const DENSITY_STEP: f64 = 0.025;
+
+/// Settings that can be changed in the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Setting {
+    Density,
+    Symmetry,
+    Seed,
+}
+
+/// Settings in the order they're shown.
+const SETTINGS: [Setting; 3] = [Setting::Density, Setting::Symmetry, Setting::Seed];
+
+/// Window to change the random soup settings and fill with a new soup.
+pub struct SoupWindow {
+    /// Settings being edited.
+    soup: Soup,
+
+    /// Digits of the seed being typed, or empty for a random seed.
+    seed: String,
+
+    /// Index into the settings of the highlighted one.
+    selected: usize,
+
+    /// Where the settings were drawn, one per line, to find the one clicked.
+    rows_area: Rect,
+
+    /// Settings and seed to fill with, once the user has chosen.
+    chosen: Option<(Soup, Option<u64>)>,
+}
+
+impl SoupWindow {
+    /// Create a new Soup window starting from the current settings.
+    pub fn new(soup: Soup) -> Self {
+        Self {
+            soup,
+            seed: String::new(),
+            selected: 0,
+            rows_area: Rect::default(),
+            chosen: None,
+        }
+    }
+
+    /// Take the settings and seed the user chose to fill with, if any.
+    ///
+    /// The seed is `None` if the user wants a random one.
+    pub fn take_chosen(&mut self) -> Option<(Soup, Option<u64>)> {
+        self.chosen.take()
+    }
+
+    /// Draw the Soup Window.
+    pub fn draw(&mut self, frame: &mut ratatui::Frame, theme: &Theme) -> Option<WindowDrawResult> {
+        let area = util::centered_area(40, 7, frame);
+
+        let block = Block::bordered()
+            .title(Line::from(" Random Soup ".bold()))
+            .title_bottom(Line::from(" ←→→Change | Enter→Fill ").centered())
+            .padding(Padding::new(2, 2, 1, 1))
+            .border_set(border::THICK)
+            .border_style(Style::new().fg(theme.dialog_border));
+
+        let lines: Vec<Line> = SETTINGS
+            .iter()
+            .enumerate()
+            .map(|(i, setting)| {
+                let (name, value, description) = match setting {
+                    Setting::Density => (
+                        "Density ",
+                        format!("{:.1}%", self.soup.density * 100.0),
+                        String::new(),
+                    ),
+                    Setting::Symmetry => (
+                        "Symmetry",
+                        self.soup.symmetry.name().to_string(),
+                        format!(" ({})", self.soup.symmetry.description()),
+                    ),
+                    Setting::Seed if self.seed.is_empty() => (
+                        "Seed    ",
+                        "random".to_string(),
+                        " (type a number)".to_string(),
+                    ),
+                    Setting::Seed => ("Seed    ", self.seed.clone(), String::new()),
+                };
+
+                let value_style = if i == self.selected {
+                    Style::new()
+                        .fg(theme.dialog_key)
+                        .add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::new().fg(theme.dialog_key)
+                };
+
+                Line::from(vec![
+                    Span::raw(format!("{name}  ")),
+                    Span::styled(format!(" {value} "), value_style),
+                    Span::styled(description, Style::new().fg(theme.dialog_dim)),
+                ])
+            })
+            .collect();
+
+        self.rows_area = block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+
+        Some(WindowDrawResult::cursor_hide())
+    }
+
+    /// Change the highlighted setting up or down a step.
+    fn change(&mut self, up: bool) {
+        match SETTINGS[self.selected] {
+            Setting::Density => {
+                let step = if up { DENSITY_STEP } else { -DENSITY_STEP };
+
+                // Round so repeated steps don't drift away from the round numbers
+                let steps = ((self.soup.density + step) / DENSITY_STEP).round();
+                self.soup.density = (steps * DENSITY_STEP).clamp(0.0, 1.0);
+            }
+
+            Setting::Symmetry => {
+                self.soup.symmetry = if up {
+                    self.soup.symmetry.next()
+                } else {
+                    self.soup.symmetry.prev()
+                };
+            }
+
+            // Seeds are typed in
+            Setting::Seed => (),
+        }
+    }
+
+    /// Handle app events for the Soup Window.
+    pub fn handle_app_event(
+        &mut self,
+        app_event: &mut AppEvent,
+        keymap: &Keymap,
+    ) -> Option<AppCommand> {
+        match &app_event.event_type {
+            AppEventType::Event(e) => match e {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    app_event.propagate = false;
+
+                    let typing_seed = SETTINGS[self.selected] == Setting::Seed;
+
+                    // Typing a seed comes first, so its digits aren't taken as other keys
+                    match (keymap.action(key_event, &ACTIONS), key_event.code) {
+                        (_, KeyCode::Char(c)) if typing_seed && c.is_ascii_digit() => {
+                            // Only keep digits that still fit in a seed
+                            self.seed.push(c);
+
+                            if self.seed.parse::<u64>().is_err() {
+                                self.seed.pop();
+                            }
+                        }
+
+                        (_, KeyCode::Backspace) if typing_seed => {
+                            self.seed.pop();
+                        }
+
+                        (Some(Action::MoveDown), _) | (_, KeyCode::Tab) => {
+                            self.selected = (self.selected + 1) % SETTINGS.len();
+                        }
+
+                        (Some(Action::MoveUp), _) | (_, KeyCode::BackTab) => {
+                            self.selected = (self.selected + SETTINGS.len() - 1) % SETTINGS.len();
+                        }
+
+                        (Some(Action::MoveRight), _) | (_, KeyCode::Char('+')) => {
+                            self.change(true);
+                        }
+
+                        (Some(Action::MoveLeft), _) | (_, KeyCode::Char('-')) => {
+                            self.change(false);
+                        }
+
+                        (Some(Action::SoupSettings | Action::Quit), _) => {
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        (_, KeyCode::Enter) => {
+                            self.chosen = Some((self.soup, self.seed.parse().ok()));
+                            return Some(AppCommand::CloseChildWindow);
+                        }
+
+                        _ => (),
+                    }
+
+                    None
+                }
+
+                // Clicking a setting highlights it
+                Event::Mouse(mouse_event) => {
+                    if mouse_event.kind == MouseEventKind::Down(MouseButton::Left)
+                        && let Some(i) =
+                            util::clicked_row(self.rows_area, mouse_event.column, mouse_event.row)
+                        && i < SETTINGS.len()
+                    {
+                        self.selected = i;
+                    }
+
+                    None
+                }
+
+                _ => None,
+            },
+
+            _ => None,
+        }
+    }
+}